@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::events::GameEvent;
+use crate::profile::Profile;
+use crate::units::{UnitKind, player::PlayerData};
+
+const CHAIN_CAPTURES: u32 = 3;
+const LEVEL_TARGET: u32 = 10;
+const NO_ITEMS_LEVEL: u32 = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    WolfByHen,
+    CleanFloor,
+    Level10,
+    Chain3,
+    NoItems
+}
+
+pub fn get_achievements() -> Vec<Achievement> {
+    vec![
+        Achievement::WolfByHen,
+        Achievement::CleanFloor,
+        Achievement::Level10,
+        Achievement::Chain3,
+        Achievement::NoItems
+    ]
+}
+
+pub fn get_achievement_name(achievement: Achievement) -> &'static str {
+    match achievement {
+        Achievement::WolfByHen => "Hen Power",
+        Achievement::CleanFloor => "Untouched",
+        Achievement::Level10 => "Deep Dive",
+        Achievement::Chain3 => "Combo",
+        Achievement::NoItems => "Purist"
+    }
+}
+
+pub fn get_achievement_description(achievement: Achievement) -> &'static str {
+    match achievement {
+        Achievement::WolfByHen => "Capture a Wolf with a Hen pattern",
+        Achievement::CleanFloor => "Clear a floor without losing armor",
+        Achievement::Level10 => "Reach level 10",
+        Achievement::Chain3 => "Chain 3 captures in one turn",
+        Achievement::NoItems => "Reach level 5 without using an item"
+    }
+}
+
+pub struct AchievementUnlockedEvent(pub Achievement);
+
+// facts about the current floor that single events can't tell
+#[derive(Default)]
+struct RunTracker {
+    armor_lost: bool
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AchievementUnlockedEvent>();
+        app.init_resource::<RunTracker>();
+        app.add_system(track_achievements);
+    }
+}
+
+fn track_achievements(
+    mut ev_game: EventReader<GameEvent>,
+    mut ev_unlocked: EventWriter<AchievementUnlockedEvent>,
+    mut tracker: ResMut<RunTracker>,
+    mut profile: ResMut<Profile>,
+    player_data: Res<PlayerData>
+) {
+    for ev in ev_game.iter() {
+        for achievement in get_event_achievements(ev, &mut tracker, &player_data) {
+            if profile.achievements.contains(&achievement) { continue; }
+
+            profile.achievements.push(achievement);
+            ev_unlocked.send(AchievementUnlockedEvent(achievement));
+        }
+    }
+}
+
+// a single event can earn several, eg. a hen capture that also completes a chain
+fn get_event_achievements(
+    ev: &GameEvent,
+    tracker: &mut RunTracker,
+    player_data: &PlayerData
+) -> Vec<Achievement> {
+    let mut earned = Vec::new();
+    match *ev {
+        GameEvent::LevelStarted { level } => {
+            tracker.armor_lost = false;
+            if level >= LEVEL_TARGET { earned.push(Achievement::Level10); }
+            if level >= NO_ITEMS_LEVEL && !player_data.used_item { earned.push(Achievement::NoItems); }
+        },
+        GameEvent::LevelCleared { .. } if !tracker.armor_lost && player_data.armor > 0 => {
+            earned.push(Achievement::CleanFloor)
+        },
+        GameEvent::ArmorBlocked { .. } => tracker.armor_lost = true,
+        GameEvent::PatternStolen { kind, pattern } => {
+            if kind == UnitKind::Wolf && pattern == UnitKind::Hen { earned.push(Achievement::WolfByHen); }
+            if player_data.turn_captures >= CHAIN_CAPTURES { earned.push(Achievement::Chain3); }
+        },
+        _ => ()
+    }
+    earned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hen_capture_can_complete_a_chain() {
+        let mut tracker = RunTracker::default();
+        let player_data = PlayerData { turn_captures: CHAIN_CAPTURES, ..Default::default() };
+        let ev = GameEvent::PatternStolen { kind: UnitKind::Wolf, pattern: UnitKind::Hen };
+        assert_eq!(
+            get_event_achievements(&ev, &mut tracker, &player_data),
+            vec![Achievement::WolfByHen, Achievement::Chain3]
+        );
+    }
+
+    #[test]
+    fn deep_level_without_items_earns_both() {
+        let mut tracker = RunTracker::default();
+        let ev = GameEvent::LevelStarted { level: LEVEL_TARGET };
+        assert_eq!(
+            get_event_achievements(&ev, &mut tracker, &PlayerData::default()),
+            vec![Achievement::Level10, Achievement::NoItems]
+        );
+
+        let player_data = PlayerData { used_item: true, ..Default::default() };
+        assert_eq!(
+            get_event_achievements(&ev, &mut tracker, &player_data),
+            vec![Achievement::Level10]
+        );
+    }
+
+    #[test]
+    fn lost_armor_spoils_the_floor() {
+        let mut tracker = RunTracker::default();
+        let player_data = PlayerData::default();
+        let blocked = GameEvent::ArmorBlocked { attacker: Entity::from_raw(0), attacker_kind: UnitKind::Fox };
+        let cleared = GameEvent::LevelCleared { level: 1 };
+        assert!(get_event_achievements(&blocked, &mut tracker, &player_data).is_empty());
+        assert!(get_event_achievements(&cleared, &mut tracker, &player_data).is_empty());
+
+        get_event_achievements(&GameEvent::LevelStarted { level: 2 }, &mut tracker, &player_data);
+        assert_eq!(
+            get_event_achievements(&cleared, &mut tracker, &player_data),
+            vec![Achievement::CleanFloor]
+        );
+    }
+}
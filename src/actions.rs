@@ -0,0 +1,285 @@
+use bevy::prelude::*;
+use bevy::input::InputSystem;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+const BINDINGS_FILE: &str = "input.ron";
+
+// what the player wants to do, independent of the device
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    // move to the pointed tile, or to the board cursor when not using the mouse
+    Move,
+    // hold to see the moves of the pointed npc
+    Inspect,
+    // cycle through the npcs to see their moves
+    InspectNext,
+    UseItem(usize),
+    // end the turn without moving
+    Wait,
+    // back to the last state the player could act from
+    Undo,
+    Pause,
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+    // drop the pointed item
+    Discard,
+    // held while clicking an item to move it left
+    Reorder,
+    DangerMap,
+    // hold to speed up the animations
+    FastForward
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Pad(GamepadButtonType)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct InputBindings(pub Vec<(Action, Vec<Binding>)>);
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings(vec![
+            (Action::Move, vec![
+                Binding::Mouse(MouseButton::Left),
+                Binding::Key(KeyCode::Space),
+                Binding::Pad(GamepadButtonType::South)
+            ]),
+            (Action::Inspect, vec![Binding::Mouse(MouseButton::Right)]),
+            (Action::InspectNext, vec![
+                Binding::Key(KeyCode::Tab),
+                Binding::Pad(GamepadButtonType::North)
+            ]),
+            (Action::UseItem(0), vec![
+                Binding::Key(KeyCode::Key1),
+                Binding::Pad(GamepadButtonType::West)
+            ]),
+            (Action::UseItem(1), vec![
+                Binding::Key(KeyCode::Key2),
+                Binding::Pad(GamepadButtonType::LeftTrigger)
+            ]),
+            (Action::UseItem(2), vec![
+                Binding::Key(KeyCode::Key3),
+                Binding::Pad(GamepadButtonType::RightTrigger)
+            ]),
+            (Action::Wait, vec![
+                Binding::Key(KeyCode::W),
+                Binding::Pad(GamepadButtonType::RightThumb)
+            ]),
+            (Action::Undo, vec![
+                Binding::Key(KeyCode::Z),
+                Binding::Pad(GamepadButtonType::LeftThumb)
+            ]),
+            (Action::Pause, vec![
+                Binding::Key(KeyCode::P),
+                Binding::Pad(GamepadButtonType::Start)
+            ]),
+            (Action::Up, vec![Binding::Key(KeyCode::Up), Binding::Pad(GamepadButtonType::DPadUp)]),
+            (Action::Down, vec![Binding::Key(KeyCode::Down), Binding::Pad(GamepadButtonType::DPadDown)]),
+            (Action::Left, vec![Binding::Key(KeyCode::Left), Binding::Pad(GamepadButtonType::DPadLeft)]),
+            (Action::Right, vec![Binding::Key(KeyCode::Right), Binding::Pad(GamepadButtonType::DPadRight)]),
+            (Action::Confirm, vec![Binding::Key(KeyCode::Return)]),
+            (Action::Back, vec![
+                Binding::Key(KeyCode::Escape),
+                Binding::Pad(GamepadButtonType::East)
+            ]),
+            // discards the item under the pointer - right click shares the binding with inspect,
+            // mouse_press_game discards when a card is hovered and inspects the board otherwise
+            (Action::Discard, vec![
+                Binding::Mouse(MouseButton::Right),
+                Binding::Mouse(MouseButton::Middle),
+                Binding::Key(KeyCode::X)
+            ]),
+            (Action::Reorder, vec![Binding::Key(KeyCode::LShift), Binding::Key(KeyCode::RShift)]),
+            (Action::DangerMap, vec![Binding::Key(KeyCode::D), Binding::Pad(GamepadButtonType::Select)]),
+            (Action::FastForward, vec![Binding::Key(KeyCode::F), Binding::Pad(GamepadButtonType::RightTrigger2)])
+        ])
+    }
+}
+
+// action state for the current frame, filled from the bindings
+#[derive(Default)]
+pub struct Actions {
+    pressed: Vec<(Action, Binding)>,
+    just_pressed: Vec<(Action, Binding)>,
+    just_released: Vec<Action>
+}
+
+impl Actions {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.iter().any(|(a, _)| *a == action)
+    }
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.iter().any(|(a, _)| *a == action)
+    }
+    pub fn just_released(&self, action: Action) -> bool {
+        self.just_released.contains(&action)
+    }
+    // the action refers to the pointer position rather than the board cursor
+    pub fn just_pressed_by_pointer(&self, action: Action) -> bool {
+        self.just_pressed.iter().any(|(a, b)| *a == action && matches!(b, Binding::Mouse(_)))
+    }
+    pub fn iter_just_pressed(&self) -> impl Iterator<Item=(Action, Binding)> + '_ {
+        self.just_pressed.iter().copied()
+    }
+    // a handled press is removed from every action sharing the binding,
+    // so e.g. an escape that cancelled a target can't also open the pause menu
+    pub fn consume(&mut self, binding: Binding) {
+        self.just_pressed.retain(|(_, b)| *b != binding);
+    }
+    // consumes the current presses, so they don't leak into the next state
+    pub fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_bindings());
+        app.init_resource::<Actions>();
+        app.add_system_to_stage(CoreStage::PreUpdate, update_actions.after(InputSystem));
+    }
+}
+
+// key and mouse names for the help text, gamepad buttons are left out
+fn get_binding_name(binding: Binding) -> Option<String> {
+    match binding {
+        Binding::Key(key) => {
+            let name = format!("{:?}", key);
+            Some(match name.as_str() {
+                "Return" => "Enter".to_string(),
+                "LShift" | "RShift" => "Shift".to_string(),
+                // Key1 -> 1
+                _ => name.strip_prefix("Key").unwrap_or(&name).to_string()
+            })
+        },
+        Binding::Mouse(button) => Some(format!("{:?} click", button)),
+        Binding::Pad(_) => None
+    }
+}
+
+// actions sharing a label are listed together
+fn get_help_label(action: Action) -> &'static str {
+    match action {
+        Action::Move => "move",
+        Action::Inspect => "hold on an enemy to see its moves",
+        Action::InspectNext => "next enemy's moves",
+        Action::UseItem(_) => "use an item",
+        Action::Wait => "wait",
+        Action::Undo => "undo",
+        Action::Pause => "pause",
+        Action::Up | Action::Down | Action::Left | Action::Right => "board cursor",
+        Action::Confirm => "confirm a target",
+        Action::Back => "cancel",
+        Action::Discard => "discard the pointed card",
+        Action::Reorder => "hold and click a card to move it left",
+        Action::DangerMap => "danger map",
+        Action::FastForward => "hold to fast-forward"
+    }
+}
+
+// built from the bindings in use, so it can't drift from input.ron
+pub fn get_help_text(bindings: &InputBindings) -> String {
+    let mut groups: Vec<(&str, Vec<String>)> = Vec::new();
+    for (action, action_bindings) in bindings.0.iter() {
+        let label = get_help_label(*action);
+        let idx = match groups.iter().position(|(l, _)| *l == label) {
+            Some(idx) => idx,
+            None => {
+                groups.push((label, Vec::new()));
+                groups.len() - 1
+            }
+        };
+        for name in action_bindings.iter().filter_map(|b| get_binding_name(*b)) {
+            if !groups[idx].1.contains(&name) { groups[idx].1.push(name); }
+        }
+    }
+    let entries: Vec<String> = groups.into_iter()
+        .filter(|(_, names)| !names.is_empty())
+        .map(|(label, names)| format!("{}: {}", names.join("/"), label))
+        .collect();
+    // two per line, to leave room for the menu
+    entries.chunks(2)
+        .map(|line| line.join("   "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// missing actions fall back to the defaults, the merged file is written back for editing
+fn load_bindings() -> InputBindings {
+    let mut bindings: InputBindings = storage::load(BINDINGS_FILE).unwrap_or_default();
+    for (action, default) in InputBindings::default().0 {
+        if bindings.0.iter().any(|(a, _)| *a == action) { continue; }
+        bindings.0.push((action, default));
+    }
+    storage::save(BINDINGS_FILE, &bindings);
+    bindings
+}
+
+pub fn update_actions(
+    bindings: Res<InputBindings>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    pad_buttons: Res<Input<GamepadButton>>,
+    mut actions: ResMut<Actions>
+) {
+    actions.pressed.clear();
+    actions.just_pressed.clear();
+    actions.just_released.clear();
+
+    for (action, action_bindings) in bindings.0.iter() {
+        for binding in action_bindings {
+            let (pressed, just_pressed, just_released) = match *binding {
+                Binding::Key(k) => (keys.pressed(k), keys.just_pressed(k), keys.just_released(k)),
+                Binding::Mouse(b) => (mouse.pressed(b), mouse.just_pressed(b), mouse.just_released(b)),
+                Binding::Pad(t) => gamepads.iter()
+                    .map(|g| GamepadButton::new(*g, t))
+                    .fold((false, false, false), |acc, b| (
+                        acc.0 || pad_buttons.pressed(b),
+                        acc.1 || pad_buttons.just_pressed(b),
+                        acc.2 || pad_buttons.just_released(b)
+                    ))
+            };
+            if pressed { actions.pressed.push((*action, *binding)); }
+            if just_pressed { actions.just_pressed.push((*action, *binding)); }
+            if just_released && !actions.just_released.contains(action) {
+                actions.just_released.push(*action);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn help_text_follows_the_bindings() {
+        let mut bindings = InputBindings::default();
+        let help = get_help_text(&bindings);
+        assert!(help.contains("Right click/Middle click/X: discard the pointed card"));
+        assert!(help.contains("Up/Down/Left/Right: board cursor"));
+        assert!(help.contains("1/2/3: use an item"));
+        assert!(help.contains("Z: undo"));
+
+        for (action, action_bindings) in bindings.0.iter_mut() {
+            if *action == Action::Undo { *action_bindings = vec![Binding::Key(KeyCode::U)]; }
+        }
+        let help = get_help_text(&bindings);
+        assert!(help.contains("U: undo"));
+        assert!(!help.contains("Z: undo"));
+    }
+}
@@ -12,12 +12,11 @@ pub fn check_asset_loading(
     match asset_server.get_group_load_state(
         asset_list.0.iter().map(|a| a.id)
     ) {
-        LoadState::Loading => {print!(".")},
         LoadState::Loaded => {
             game_state.set(crate::states::GameState::MainMenu);
         },
         LoadState::Failed => {
-            println!("asset loading error");
+            error!("asset loading error");
         },
         _ => {}
     };
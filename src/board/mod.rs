@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use rand::Rng;
 use std::collections::HashMap;
 
+use crate::run::RunRng;
 use crate::vectors::Vector2Int;
 use crate::states::GameState;
 
@@ -33,7 +34,7 @@ impl Plugin for BoardPlugin {
                 .with_system(generate_board)
         );
         app.add_system_set(
-            SystemSet::on_exit(GameState::GameOver)
+            SystemSet::on_enter(GameState::MainMenu)
                 .with_system(clear_board)
         );
         app.add_event::<tile::TileInteractionEvent>();
@@ -50,8 +51,9 @@ pub struct Board {
 pub fn generate_board(
     mut commands: Commands,
     mut game_state: ResMut<State<GameState>>,
+    mut run_rng: ResMut<RunRng>
 ) {
-    let mut rng = rand::thread_rng();
+    let rng = &mut run_rng.rng;
     let mut tiles = HashMap::new();
     let stair_v = Vector2Int::new(rng.gen_range(0..SIZE) as i32, rng.gen_range(0..SIZE/2) as i32);
 
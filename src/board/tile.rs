@@ -1,12 +1,20 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::command::{CommandEvent, CommandType};
-use crate::units::Unit;
+use crate::events::GameEvent;
+use crate::items::Relic;
+use crate::units::{
+    Unit,
+    player::{Player, PlayerData},
+    status::{StatusEffect, StatusKind}
+};
 
 use super::Position;
 
 pub struct TileInteractionEvent(pub Entity);
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TileKind {
     Floor,
     Wall,
@@ -19,14 +27,34 @@ pub struct Tile {
     pub kind: TileKind
 }
 
+pub fn get_tile_types() -> Vec<TileKind> {
+    vec![
+        TileKind::Floor,
+        TileKind::Wall,
+        TileKind::Stair,
+        TileKind::Bush
+    ]
+}
+
+pub fn get_tile_description(kind: &TileKind) -> Option<&'static str> {
+    match kind {
+        TileKind::Floor => None,
+        TileKind::Wall => Some("Wall: blocks the way"),
+        TileKind::Stair => Some("Stair: leads to the next level"),
+        TileKind::Bush => Some("Bush: lose your next turn")
+    }
+}
+
 pub fn tile_interaction(
     mut ev_interaction: EventReader<TileInteractionEvent>,
     mut ev_command: EventWriter<CommandEvent>,
-    mut unit_query: Query<(&mut Unit, &Position)>,
-    tile_query: Query<(&Tile, &Position)>
+    mut ev_game: EventWriter<GameEvent>,
+    mut unit_query: Query<(&mut Unit, &Position, Option<&Player>)>,
+    tile_query: Query<(&Tile, &Position)>,
+    player_data: Res<PlayerData>
 ) {
     for ev in ev_interaction.iter() {
-        if let Ok((mut _unit, position)) = unit_query.get_mut(ev.0) {
+        if let Ok((unit, position, player)) = unit_query.get_mut(ev.0) {
             let (tile, _) = match tile_query.iter()
                 .filter(|(_, p)| p.v == position.v)
                 .next() {
@@ -34,11 +62,14 @@ pub fn tile_interaction(
                     _ => return
                 };
             
-            match tile.kind {
-                TileKind::Bush => {
-                    ev_command.send(CommandEvent(CommandType::PauseUnit(ev.0)));
-                },
-                _ => ()
+            if tile.kind == TileKind::Bush {
+                let immune = player.is_some() && player_data.relics.contains(&Relic::BushImmunity);
+                ev_game.send(GameEvent::BushEntered { kind: unit.kind, paused: !immune });
+                if immune { continue; }
+                ev_command.send(CommandEvent(CommandType::ApplyStatus(
+                    ev.0,
+                    StatusEffect::new(StatusKind::Stunned, 1)
+                )));
             }
         }
     }
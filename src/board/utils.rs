@@ -1,4 +1,4 @@
-use rand::prelude::SliceRandom;
+use rand::{Rng, prelude::SliceRandom};
 
 use crate::vectors::Vector2Int;
 
@@ -7,12 +7,10 @@ use super::Board;
 pub fn get_spawn_position(
     blocker_positions: &Vec<Vector2Int>,
     board: &Board,
+    rng: &mut impl Rng
 ) -> Option<Vector2Int> {
     let positions = get_possible_spawn_positions(blocker_positions, board);
-    match positions.choose(&mut rand::thread_rng()) {
-        Some(v) => Some(*v),
-        None => None
-    }
+    positions.choose(rng).copied()
 }
 
 fn get_possible_spawn_positions(
@@ -20,12 +18,15 @@ fn get_possible_spawn_positions(
     board: &Board
 ) -> Vec<Vector2Int> {
 
-    board.tiles.keys()
+    let mut positions: Vec<Vector2Int> = board.tiles.keys()
         .filter(|v|
             !blocker_positions
                 .iter()
                 .any(|a| a == *v)
         )
         .map(|v| *v)
-        .collect()
+        .collect();
+    // map order is random, seeded runs need a stable one
+    positions.sort_by_key(|v| (v.y, v.x));
+    positions
 }
\ No newline at end of file
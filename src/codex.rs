@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+use crate::board::tile::Tile;
+use crate::items::{Item, shop::Interlude};
+use crate::profile::Profile;
+use crate::units::{Unit, player::PlayerData};
+
+pub struct CodexPlugin;
+
+impl Plugin for CodexPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(track_codex);
+    }
+}
+
+// unlocks codex entries the first time they show up in a run
+fn track_codex(
+    mut profile: ResMut<Profile>,
+    unit_query: Query<&Unit, Added<Unit>>,
+    tile_query: Query<&Tile, Added<Tile>>,
+    item_query: Query<&Item, Added<Item>>,
+    player_data: Res<PlayerData>,
+    interlude: Option<Res<Interlude>>
+) {
+    for unit in unit_query.iter() {
+        if profile.seen_units.contains(&unit.kind) { continue; }
+        profile.seen_units.push(unit.kind);
+    }
+    for tile in tile_query.iter() {
+        if profile.seen_tiles.contains(&tile.kind) { continue; }
+        profile.seen_tiles.push(tile.kind);
+    }
+
+    let mut items: Vec<_> = item_query.iter().map(|i| i.kind).collect();
+    if player_data.is_changed() {
+        items.extend(player_data.items.iter().map(|i| i.kind));
+    }
+    if let Some(interlude) = interlude {
+        if interlude.is_changed() {
+            items.extend(interlude.offers.iter().map(|o| o.kind));
+        }
+    }
+    for kind in items {
+        if profile.seen_items.contains(&kind) { continue; }
+        profile.seen_items.push(kind);
+    }
+}
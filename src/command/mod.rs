@@ -1,116 +1,329 @@
 use bevy::prelude::*;
+use std::collections::VecDeque;
 
+use crate::board::Position;
+use crate::events::GameEvent;
+use crate::graphics::{TILE_SIZE, UNIT_Z};
+use crate::items::Relic;
+use crate::settings::Settings;
 use crate::ui::RedrawUIEvent;
 use crate::units::{
     player::{Player, PlayerData},
     Unit,
-    UnitState
+    status::{StatusEffect, StatusKind}
 };
+use crate::vectors::Vector2Int;
 
+#[derive(Clone, Copy, Debug)]
 pub enum CommandType {
     AddAP(Entity, u8),
     RemoveAP(Entity),
-    PauseUnit(Entity),
-    AttackUnit(Entity, Entity)
+    ApplyStatus(Entity, StatusEffect),
+    AttackUnit(Entity, Entity),
+    KillUnit(Entity),
+    Teleport(Entity, Vector2Int),
+    SwapUnits(Entity, Entity),
+    RevealIntents,
+    // the player learns the pattern of the unit
+    CopyPattern(Entity),
+    PhaseWalls,
+    AddArmor(u8),
+    RemoveArmor(u8),
+    AddRelic(Relic)
 }
 
 pub struct CommandEvent(pub CommandType);
 
+// what a command actually did, for the systems reacting to it
+#[derive(Clone, Copy, Debug)]
+pub enum Outcome {
+    // the target no longer exists
+    Skipped,
+    APChanged { from: u8, to: u8 },
+    StatusApplied,
+    // the attack was resolved with follow-up commands
+    Attacked,
+    ArmorBlocked,
+    Killed,
+    Moved { from: Vector2Int, to: Vector2Int },
+    Swapped { a: Vector2Int, b: Vector2Int },
+    ArmorChanged { from: u8, to: u8 },
+    PlayerFlag,
+    RelicAdded
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ResolvedCommand {
+    pub command: CommandType,
+    pub outcome: Outcome
+}
+
+pub struct CommandResolvedEvent(pub ResolvedCommand);
+
 pub struct CommandPlugin;
 impl Plugin for CommandPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<CommandEvent>();
-        app.add_system(add_ap);
-        app.add_system(remove_ap);
-        app.add_system(pause_unit);
-        app.add_system(attack_unit);
+        app.add_event::<CommandResolvedEvent>();
+        // runs after the other systems of the stage, so despawns can't clash with them
+        app.add_system(process_commands.exclusive_system().at_end());
     }
 }
 
-fn add_ap(
-    mut ev_command: EventReader<CommandEvent>,
-    mut unit_query: Query<&mut Unit>,
-    mut ev_redraw_ui: EventWriter<RedrawUIEvent>
-) {
-    for ev in ev_command.iter() {
-        match ev.0 {
-            CommandType::AddAP(e, ap) => {
-                if let Ok(mut unit) = unit_query.get_mut(e) {
-                    unit.ap += ap;
-                    ev_redraw_ui.send(RedrawUIEvent);
-                }
-            },
-            _ => ()
-        }
+fn process_commands(world: &mut World) {
+    let commands: Vec<CommandType> = world.resource_mut::<Events<CommandEvent>>()
+        .drain()
+        .map(|ev| ev.0)
+        .collect();
+
+    let resolved = resolve_commands(world, commands);
+    let redraw = resolved.iter().any(|r| !matches!(r.outcome, Outcome::Skipped));
+
+    world.resource_mut::<Events<CommandResolvedEvent>>()
+        .extend(resolved.into_iter().map(CommandResolvedEvent));
+    if redraw {
+        world.resource_mut::<Events<RedrawUIEvent>>().send(RedrawUIEvent);
     }
 }
 
-fn remove_ap(
-    mut ev_command: EventReader<CommandEvent>,
-    mut unit_query: Query<&mut Unit>,
-    mut ev_redraw_ui: EventWriter<RedrawUIEvent>
-) {
-    for ev in ev_command.iter() {
-        match ev.0 {
-            CommandType::RemoveAP(e) => {
-                if let Ok(mut unit) = unit_query.get_mut(e) {
-                    unit.ap = 0;
-                    ev_redraw_ui.send(RedrawUIEvent);
-                }
-            },
-            _ => ()
-        }
+// resolves the commands in order, together with their follow-ups
+pub fn resolve_commands(
+    world: &mut World,
+    commands: impl IntoIterator<Item=CommandType>
+) -> Vec<ResolvedCommand> {
+    let mut queue: VecDeque<CommandType> = commands.into_iter().collect();
+    let mut resolved = Vec::new();
+    while let Some(command) = queue.pop_front() {
+        let outcome = resolve(world, command, &mut queue);
+        debug!("command {:?} -> {:?}", command, outcome);
+        resolved.push(ResolvedCommand { command, outcome });
     }
+    resolved
 }
 
-fn pause_unit(
-    mut ev_command: EventReader<CommandEvent>,
-    mut unit_query: Query<&mut Unit>
-) {
-    for ev in ev_command.iter() {
-        match ev.0 {
-            CommandType::PauseUnit(e) => {
-                if let Ok(mut unit) = unit_query.get_mut(e) {
-                    unit.state = UnitState::Paused;
+// applies a single command, follow-up commands are pushed to the back of the queue
+fn resolve(
+    world: &mut World,
+    command: CommandType,
+    queue: &mut VecDeque<CommandType>
+) -> Outcome {
+    match command {
+        CommandType::AddAP(e, ap) => {
+            match world.get_mut::<Unit>(e) {
+                Some(mut unit) => {
+                    let from = unit.ap;
+                    unit.ap += ap;
+                    Outcome::APChanged { from, to: unit.ap }
+                },
+                None => Outcome::Skipped
+            }
+        },
+        CommandType::RemoveAP(e) => {
+            match world.get_mut::<Unit>(e) {
+                Some(mut unit) => {
+                    let from = unit.ap;
                     unit.ap = 0;
+                    Outcome::APChanged { from, to: 0 }
+                },
+                None => Outcome::Skipped
+            }
+        },
+        CommandType::ApplyStatus(e, effect) => {
+            match world.get_mut::<Unit>(e) {
+                Some(mut unit) => {
+                    // a stun also ends the current turn
+                    if effect.kind == StatusKind::Stunned { unit.ap = 0; }
+                    unit.effects.add(effect);
+                    Outcome::StatusApplied
+                },
+                None => Outcome::Skipped
+            }
+        },
+        CommandType::AttackUnit(attacker, defender) => {
+            let (attacker_kind, defender_kind) = match (world.get::<Unit>(attacker), world.get::<Unit>(defender)) {
+                (Some(a), Some(d)) => (a.kind, d.kind),
+                _ => return Outcome::Skipped
+            };
+            if world.get::<Player>(defender).is_some() && world.resource::<PlayerData>().armor > 0 {
+                // armor absorbs the hit and the attacker dies
+                queue.push_back(CommandType::RemoveArmor(1));
+                queue.push_back(CommandType::KillUnit(attacker));
+                send_game_event(world, GameEvent::ArmorBlocked { attacker, attacker_kind });
+                return Outcome::ArmorBlocked;
+            }
+            queue.push_back(CommandType::KillUnit(defender));
+            send_game_event(world, GameEvent::UnitCaptured {
+                attacker,
+                attacker_kind,
+                victim: defender,
+                victim_kind: defender_kind
+            });
+            Outcome::Attacked
+        },
+        CommandType::KillUnit(e) => {
+            let (kind, v) = match (world.get::<Unit>(e), world.get::<Position>(e)) {
+                (Some(unit), Some(position)) => (unit.kind, position.v),
+                _ => return Outcome::Skipped
+            };
+            despawn_with_children_recursive(world, e);
+            send_game_event(world, GameEvent::UnitKilled { entity: e, kind, v });
+            Outcome::Killed
+        },
+        CommandType::Teleport(e, v) => {
+            let from = match world.get_mut::<Position>(e) {
+                Some(mut position) => std::mem::replace(&mut position.v, v),
+                None => return Outcome::Skipped
+            };
+            // teleports are instant - skip the move animation
+            if let Some(mut transform) = world.get_mut::<Transform>(e) {
+                transform.translation = get_unit_translation(v);
+            }
+            Outcome::Moved { from, to: v }
+        },
+        CommandType::SwapUnits(a, b) => {
+            let (va, vb) = match (world.get::<Position>(a), world.get::<Position>(b)) {
+                (Some(pa), Some(pb)) => (pa.v, pb.v),
+                _ => return Outcome::Skipped
+            };
+            for (e, v) in [(a, vb), (b, va)] {
+                if let Some(mut position) = world.get_mut::<Position>(e) {
+                    position.v = v;
+                }
+                if let Some(mut transform) = world.get_mut::<Transform>(e) {
+                    transform.translation = get_unit_translation(v);
                 }
-            },
-            _ => ()
+            }
+            Outcome::Swapped { a: va, b: vb }
+        },
+        CommandType::RevealIntents => {
+            world.resource_mut::<PlayerData>().reveal_intents = true;
+            Outcome::PlayerFlag
+        },
+        CommandType::CopyPattern(e) => {
+            let (kind, behaviour) = match world.get::<Unit>(e) {
+                Some(unit) => (unit.kind, unit.behaviour.clone()),
+                None => return Outcome::Skipped
+            };
+            let mode = world.resource::<Settings>().pattern_mode;
+            world.resource_mut::<PlayerData>().learn_pattern(kind, behaviour, mode);
+            Outcome::PlayerFlag
+        },
+        CommandType::PhaseWalls => {
+            world.resource_mut::<PlayerData>().phasing = true;
+            Outcome::PlayerFlag
+        },
+        CommandType::AddArmor(armor) => {
+            let mut player_data = world.resource_mut::<PlayerData>();
+            let from = player_data.armor;
+            player_data.armor += armor;
+            Outcome::ArmorChanged { from, to: player_data.armor }
+        },
+        CommandType::RemoveArmor(armor) => {
+            let mut player_data = world.resource_mut::<PlayerData>();
+            let from = player_data.armor;
+            player_data.armor = from.saturating_sub(armor);
+            Outcome::ArmorChanged { from, to: player_data.armor }
+        },
+        CommandType::AddRelic(relic) => {
+            world.resource_mut::<PlayerData>().add_relic(relic);
+            Outcome::RelicAdded
         }
     }
 }
 
-fn attack_unit(
-    mut commands: Commands,
-    mut ev_command: EventReader<CommandEvent>,
-    mut unit_query: Query<(&mut Unit, Option<&Player>)>,
-    mut player_data: ResMut<PlayerData>
-) {
-    for ev in ev_command.iter() {
-        match ev.0 {
-            CommandType::AttackUnit(attacker, defender) => {
-                let (_defender_unit, player) = match unit_query.get(defender) {
-                    Ok((u, p)) => (u, p),
-                    Err(_) => continue
-                };
-
-                if player.is_some() {
-                    match player_data.armor {
-                        a if a > 0 => {
-                            // player has armor
-                            player_data.armor -= 1;
-                            commands.entity(attacker).despawn_recursive();
-                        },
-                        _ => {
-                            // no armor, kill player
-                            commands.entity(defender).despawn_recursive();
-                        }
-                    }
-                } else {
-                    commands.entity(defender).despawn_recursive();
-                }
-            },
-            _ => ()
-        }
+fn send_game_event(world: &mut World, event: GameEvent) {
+    world.resource_mut::<Events<GameEvent>>().send(event);
+}
+
+fn get_unit_translation(v: Vector2Int) -> Vec3 {
+    Vec3::new(
+        (v.x as f32 + 0.5) * TILE_SIZE,
+        (v.y as f32 + 0.5) * TILE_SIZE,
+        UNIT_Z
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::units::{UnitKind, data::get_unit_behaviour, status::StatusEffects};
+    use super::*;
+
+    fn setup(armor: u8) -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Events<GameEvent>>();
+        world.insert_resource(PlayerData { armor, ..Default::default() });
+        let player = spawn_unit(&mut world, UnitKind::Player, Vector2Int::new(0, 0));
+        world.entity_mut(player).insert(Player);
+        let npc = spawn_unit(&mut world, UnitKind::Wolf, Vector2Int::new(1, 0));
+        (world, player, npc)
+    }
+
+    fn spawn_unit(world: &mut World, kind: UnitKind, v: Vector2Int) -> Entity {
+        world.spawn()
+            .insert(Unit {
+                ap: 1,
+                behaviour: get_unit_behaviour(&kind),
+                kind,
+                effects: StatusEffects::default()
+            })
+            .insert(Position { v })
+            .id()
+    }
+
+    #[test]
+    fn follow_ups_go_to_the_back_of_the_queue() {
+        let (mut world, player, npc) = setup(0);
+        let resolved = resolve_commands(&mut world, [
+            CommandType::AttackUnit(player, npc),
+            CommandType::AddAP(player, 1)
+        ]);
+        assert!(matches!(resolved[..], [
+            ResolvedCommand { command: CommandType::AttackUnit(..), outcome: Outcome::Attacked },
+            ResolvedCommand { command: CommandType::AddAP(..), outcome: Outcome::APChanged { from: 1, to: 2 } },
+            ResolvedCommand { command: CommandType::KillUnit(e), outcome: Outcome::Killed }
+        ] if e == npc));
+        assert!(world.get_entity(npc).is_none());
+    }
+
+    #[test]
+    fn armor_blocks_an_attack_on_the_player() {
+        let (mut world, player, npc) = setup(1);
+        let resolved = resolve_commands(&mut world, [CommandType::AttackUnit(npc, player)]);
+        assert!(matches!(resolved[..], [
+            ResolvedCommand { outcome: Outcome::ArmorBlocked, .. },
+            ResolvedCommand { command: CommandType::RemoveArmor(1), outcome: Outcome::ArmorChanged { from: 1, to: 0 } },
+            ResolvedCommand { command: CommandType::KillUnit(e), outcome: Outcome::Killed }
+        ] if e == npc));
+        assert_eq!(world.resource::<PlayerData>().armor, 0);
+        assert!(world.get_entity(player).is_some());
+        assert!(world.get_entity(npc).is_none());
+    }
+
+    #[test]
+    fn attack_without_armor_kills_the_player() {
+        let (mut world, player, npc) = setup(0);
+        let resolved = resolve_commands(&mut world, [CommandType::AttackUnit(npc, player)]);
+        assert!(matches!(resolved[..], [
+            ResolvedCommand { outcome: Outcome::Attacked, .. },
+            ResolvedCommand { command: CommandType::KillUnit(e), outcome: Outcome::Killed }
+        ] if e == player));
+        assert!(world.get_entity(player).is_none());
+    }
+
+    #[test]
+    fn commands_on_despawned_entities_are_skipped() {
+        let (mut world, player, npc) = setup(0);
+        world.despawn(npc);
+        let resolved = resolve_commands(&mut world, [
+            CommandType::AttackUnit(player, npc),
+            CommandType::AddAP(npc, 1),
+            CommandType::RemoveAP(npc),
+            CommandType::KillUnit(npc),
+            CommandType::Teleport(npc, Vector2Int::new(2, 2)),
+            CommandType::SwapUnits(player, npc)
+        ]);
+        assert_eq!(resolved.len(), 6);
+        assert!(resolved.iter().all(|r| matches!(r.outcome, Outcome::Skipped)));
+        assert_eq!(world.get::<Position>(player).unwrap().v, Vector2Int::new(0, 0));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use crate::items::ItemKind;
+use crate::units::{UnitKind, behaviour::MoveKind};
+use crate::vectors::Vector2Int;
+
+// domain events for systems that only observe the game (ui, audio, statistics)
+#[derive(Clone, Copy, Debug)]
+pub enum GameEvent {
+    LevelStarted { level: u32 },
+    LevelCleared { level: u32 },
+    // a move made with the unit's pattern, teleports and swaps are not included
+    UnitMoved {
+        entity: Entity,
+        move_kind: MoveKind,
+        from: Vector2Int,
+        to: Vector2Int
+    },
+    // a unit moved onto another one
+    UnitCaptured {
+        attacker: Entity,
+        attacker_kind: UnitKind,
+        victim: Entity,
+        victim_kind: UnitKind
+    },
+    // v is where the unit was removed from the board
+    UnitKilled { entity: Entity, kind: UnitKind, v: Vector2Int },
+    ArmorBlocked { attacker: Entity, attacker_kind: UnitKind },
+    // pattern is the one the capturing move was made with
+    PatternStolen { kind: UnitKind, pattern: UnitKind },
+    ItemPicked { kind: ItemKind },
+    ItemUsed { kind: ItemKind },
+    // paused is false when the unit was immune
+    BushEntered { kind: UnitKind, paused: bool },
+    PlayerDied { level: u32 }
+}
+
+pub struct EventsPlugin;
+
+impl Plugin for EventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GameEvent>();
+    }
+}
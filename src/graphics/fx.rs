@@ -1,17 +1,58 @@
 use bevy::{
     prelude::*,
     render,
-    sprite
+    sprite::{self, Anchor}
 };
+use std::collections::HashMap;
+use std::f32::consts::PI;
 
-use crate::board;
+use crate::board::{self, Position};
+use crate::command::{CommandResolvedEvent, CommandType, Outcome};
+use crate::events::GameEvent;
 use crate::states::{FadeState, GameState};
+use crate::ui::FontAssets;
+use crate::units::{Unit, UnitKind, data::get_unit_rank, player::Player};
+use crate::vectors::Vector2Int;
 
-use super::OVERLAY_Z;
+use super::{FX_Z, OVERLAY_Z, PlaybackSpeed, TILE_SIZE};
 use super::board_renderer::MASK_RANGE;
+use super::palette::Palette;
 
 const FADE_RATE: f32 = 5.;
 
+const LUNGE_RATE: f32 = 6.;
+// fraction of the sprite size
+const LUNGE_DISTANCE: f32 = 0.3;
+const POOF_PARTICLES: usize = 8;
+const POOF_SPEED: f32 = 80.;
+const POOF_COLOR: Color = Color::Rgba { red: 0.9, green: 0.9, blue: 0.85, alpha: 1. };
+const NUMBER_SPEED: f32 = 40.;
+const NUMBER_LINE: f32 = 24.;
+
+// effects are cosmetic entities with their own timers - the turns never wait for them
+
+pub struct FxAssets {
+    shield: Handle<Image>
+}
+
+// the attacker's sprite is pushed forward and back, the transform is left to the move animation
+#[derive(Component)]
+pub struct Lunge {
+    dir: Vec2,
+    progress: f32
+}
+
+// a short lived sprite or text, removed when the progress reaches 1
+#[derive(Component)]
+pub struct Effect {
+    velocity: Vec3,
+    progress: f32,
+    rate: f32,
+    end_scale: f32
+}
+
+type EffectItem<'a> = (Entity, &'a mut Effect, &'a mut Transform, Option<&'a mut Sprite>, Option<&'a mut Text>);
+
 #[derive(Component)]
 pub struct Overlay {
     pub counter: f32
@@ -73,18 +114,19 @@ pub fn clear_overlay(
 pub fn fade_overlay_in(
     mut query: Query<(&Handle<ColorMaterial>, &mut Overlay)>,
     time: Res<Time>,
+    playback: Res<PlaybackSpeed>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut fade_state: ResMut<State<FadeState>>,
     mut game_state: ResMut<State<GameState>>,
 ) {
     if let Ok((handle, mut overlay)) = query.get_single_mut() {
-        if let Some(mut material) = materials.get_mut(handle) {
-            overlay.counter += FADE_RATE * time.delta_seconds();
+        if let Some(material) = materials.get_mut(handle) {
+            overlay.counter += FADE_RATE * playback.multiplier * time.delta_seconds();
             material.color.set_a(ease(overlay.counter));
             if overlay.counter >= 1. { 
                 fade_state.set(FadeState::Out);
                 match game_state.current() {
-                    GameState::PlayerTurn => game_state.set(GameState::MapGenerate).unwrap(),
+                    GameState::PlayerTurn => game_state.set(GameState::Interlude).unwrap(),
                     GameState::MainMenu => game_state.set(GameState::MapGenerate).unwrap(),
                     _ => ()
                 }
@@ -96,12 +138,13 @@ pub fn fade_overlay_in(
 pub fn fade_overlay_out(
     mut query: Query<(&Handle<ColorMaterial>, &mut Overlay)>,
     time: Res<Time>,
+    playback: Res<PlaybackSpeed>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut fade_state: ResMut<State<FadeState>>
 ) {
     if let Ok((handle, mut overlay)) = query.get_single_mut() {
-        if let Some(mut material) = materials.get_mut(handle) {
-            overlay.counter -= FADE_RATE * time.delta_seconds();
+        if let Some(material) = materials.get_mut(handle) {
+            overlay.counter -= FADE_RATE * playback.multiplier * time.delta_seconds();
             material.color.set_a(ease(overlay.counter));
 
             if overlay.counter <= 0. { fade_state.set(FadeState::Hidden); }
@@ -111,4 +154,223 @@ pub fn fade_overlay_out(
 
 fn ease(t: f32) -> f32 {
     t * t * (3. - 2. * t)
-}
\ No newline at end of file
+}
+
+pub fn load_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut asset_list: ResMut<crate::assets::AssetList>
+) {
+    let shield = asset_server.load("shield.png");
+    asset_list.0.push(shield.clone_untyped());
+    commands.insert_resource(FxAssets { shield });
+}
+
+pub fn spawn_effects(
+    mut commands: Commands,
+    mut ev_game: EventReader<GameEvent>,
+    mut ev_resolved: EventReader<CommandResolvedEvent>,
+    // direction of the last move of every unit, the lunge follows it
+    mut last_moves: Local<HashMap<Entity, Vector2Int>>,
+    unit_query: Query<&Position, With<Unit>>,
+    player_query: Query<&Position, With<Player>>,
+    // both are inserted on LoadAssets, the system runs from the first frame
+    assets: Option<Res<FxAssets>>,
+    font_assets: Option<Res<FontAssets>>,
+    palette: Res<Palette>
+) {
+    let (assets, font_assets) = match (assets, font_assets) {
+        (Some(a), Some(f)) => (a, f),
+        _ => return
+    };
+    for ev in ev_game.iter() {
+        match *ev {
+            GameEvent::UnitMoved { entity, from, to, .. } => {
+                last_moves.insert(entity, to - from);
+            },
+            GameEvent::UnitCaptured { attacker, attacker_kind, victim_kind, .. } => {
+                let position = match unit_query.get(attacker) {
+                    Ok(p) => p,
+                    Err(_) => continue
+                };
+                let dir = last_moves.get(&attacker).copied().unwrap_or_default();
+                commands.entity(attacker).insert(Lunge {
+                    dir: Vec2::new(dir.x as f32, dir.y as f32).normalize_or_zero(),
+                    progress: 0.
+                });
+                if attacker_kind == UnitKind::Player {
+                    // the coins the capture was worth
+                    let text = format!("+{}", get_unit_rank(&victim_kind));
+                    spawn_number(&mut commands, position.v, 0, text, palette.status, &font_assets);
+                }
+            },
+            GameEvent::UnitKilled { entity, v, .. } => {
+                last_moves.remove(&entity);
+                spawn_poof(&mut commands, v);
+            },
+            _ => ()
+        }
+    }
+    for ev in ev_resolved.iter() {
+        match (ev.0.command, ev.0.outcome) {
+            // teleports and swaps skip the move animation, so both tiles get a poof
+            (_, Outcome::Moved { from: a, to: b }) | (_, Outcome::Swapped { a, b }) => {
+                spawn_poof(&mut commands, a);
+                spawn_poof(&mut commands, b);
+            },
+            (CommandType::AddAP(e, _), Outcome::APChanged { from, to }) if to > from => {
+                if let Ok(position) = unit_query.get(e) {
+                    // one line below a capture's coins
+                    let text = format!("+{} AP", to - from);
+                    spawn_number(&mut commands, position.v, 1, text, palette.status, &font_assets);
+                }
+            },
+            (_, Outcome::ArmorChanged { from, to }) if to != from => {
+                if let Ok(position) = player_query.get_single() {
+                    let (text, color) = match to > from {
+                        true => (format!("+{}", to - from), palette.status),
+                        false => (format!("-{}", from - to), palette.danger)
+                    };
+                    spawn_shield_flash(&mut commands, position.v, &assets);
+                    spawn_number(&mut commands, position.v, 0, text, color, &font_assets);
+                }
+            },
+            _ => ()
+        }
+    }
+}
+
+fn get_tile_center(v: Vector2Int, z: f32) -> Vec3 {
+    Vec3::new((v.x as f32 + 0.5) * TILE_SIZE, (v.y as f32 + 0.5) * TILE_SIZE, z)
+}
+
+fn spawn_poof(commands: &mut Commands, v: Vector2Int) {
+    let center = get_tile_center(v, FX_Z);
+    commands.spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: POOF_COLOR,
+                custom_size: Some(Vec2::splat(0.5 * TILE_SIZE)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(center),
+            ..Default::default()
+        })
+        .insert(Effect { velocity: Vec3::ZERO, progress: 0., rate: 3., end_scale: 1.5 });
+
+    for idx in 0..POOF_PARTICLES {
+        let angle = 2. * PI * idx as f32 / POOF_PARTICLES as f32;
+        commands.spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: POOF_COLOR,
+                    custom_size: Some(Vec2::splat(0.125 * TILE_SIZE)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(center),
+                ..Default::default()
+            })
+            .insert(Effect {
+                velocity: POOF_SPEED * Vec3::new(angle.cos(), angle.sin(), 0.),
+                progress: 0.,
+                rate: 2.,
+                end_scale: 0.2
+            });
+    }
+}
+
+fn spawn_shield_flash(commands: &mut Commands, v: Vector2Int, assets: &FxAssets) {
+    commands.spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(0.75 * TILE_SIZE)),
+                ..Default::default()
+            },
+            texture: assets.shield.clone(),
+            transform: Transform::from_translation(get_tile_center(v, FX_Z)),
+            ..Default::default()
+        })
+        .insert(Effect { velocity: Vec3::ZERO, progress: 0., rate: 2., end_scale: 1.6 });
+}
+
+fn spawn_number(
+    commands: &mut Commands,
+    v: Vector2Int,
+    // numbers spawned together are stacked downwards
+    line: usize,
+    text: String,
+    color: Color,
+    font_assets: &FontAssets
+) {
+    let offset = 0.5 * TILE_SIZE - line as f32 * NUMBER_LINE;
+    let translation = get_tile_center(v, FX_Z) + Vec3::new(0., offset, 0.);
+    commands.spawn_bundle(Text2dBundle {
+            text: Text::from_section(
+                text,
+                TextStyle {
+                    color,
+                    font: font_assets.font.clone(),
+                    font_size: 24.
+                }
+            ).with_alignment(TextAlignment::CENTER),
+            transform: Transform::from_translation(translation),
+            ..Default::default()
+        })
+        .insert(Effect { velocity: Vec3::new(0., NUMBER_SPEED, 0.), progress: 0., rate: 1.5, end_scale: 1. });
+}
+
+pub fn animate_lunges(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Lunge, &mut TextureAtlasSprite)>,
+    time: Res<Time>,
+    playback: Res<PlaybackSpeed>,
+    game_state: Res<State<GameState>>
+) {
+    if game_state.current() == &GameState::Paused { return; }
+    for (entity, mut lunge, mut sprite) in query.iter_mut() {
+        lunge.progress += LUNGE_RATE * playback.multiplier * time.delta_seconds();
+        if lunge.progress >= 1. {
+            sprite.anchor = Anchor::Center;
+            commands.entity(entity).remove::<Lunge>();
+            continue;
+        }
+        // the anchor moves opposite to the sprite
+        let offset = LUNGE_DISTANCE * (lunge.progress * PI).sin();
+        sprite.anchor = Anchor::Custom(-offset * lunge.dir);
+    }
+}
+
+pub fn animate_effects(
+    mut commands: Commands,
+    mut query: Query<EffectItem>,
+    time: Res<Time>,
+    playback: Res<PlaybackSpeed>,
+    game_state: Res<State<GameState>>
+) {
+    if game_state.current() == &GameState::Paused { return; }
+    let delta = playback.multiplier * time.delta_seconds();
+    for (entity, mut effect, mut transform, sprite, text) in query.iter_mut() {
+        effect.progress += effect.rate * delta;
+        if effect.progress >= 1. {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation += effect.velocity * delta;
+        transform.scale = Vec3::splat(1. + (effect.end_scale - 1.) * effect.progress);
+        let alpha = 1. - ease(effect.progress);
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_a(alpha);
+        }
+        if let Some(mut text) = text {
+            for section in text.sections.iter_mut() {
+                section.style.color.set_a(alpha);
+            }
+        }
+    }
+}
+
+pub fn clear_effects(
+    mut commands: Commands,
+    query: Query<Entity, With<Effect>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -48,7 +48,7 @@ pub fn load_assets(
     let image_handle = asset_server.load("items.png");
     asset_list.0.push(image_handle.clone_untyped());
 
-    let atlas = TextureAtlas::from_grid(image_handle, Vec2::splat(16.0), 2, 2);
+    let atlas = TextureAtlas::from_grid(image_handle, Vec2::splat(16.0), 4, 5);
 
     let atlas_handle = texture_atlases.add(atlas);
     commands.insert_resource(ItemSprites(atlas_handle));
@@ -57,7 +57,24 @@ pub fn load_assets(
 fn get_sprite_idx(kind: &ItemKind) -> usize {
     match kind {
         ItemKind::StopMushroom => 0,
-        ItemKind::SpeedMushroom => 2,
+        ItemKind::SpeedMushroom => 4,
         ItemKind::Armor => 1,
+        ItemKind::Teleport => 2,
+        ItemKind::Swap => 3,
+        ItemKind::Freeze => 5,
+        ItemKind::Bomb => 6,
+        ItemKind::Eye => 7,
+        ItemKind::Ghost => 8,
+        ItemKind::Frost => 9,
+        ItemKind::Wings => 10,
+        ItemKind::Spring => 11,
+        ItemKind::Crown => 12,
+        ItemKind::Machete => 13,
+        ItemKind::Backpack => 14,
+        ItemKind::Coffee => 15,
+        ItemKind::Snail => 16,
+        ItemKind::Vine => 17,
+        ItemKind::Venom => 18,
+        ItemKind::Mirror => 19,
     }
 }
\ No newline at end of file
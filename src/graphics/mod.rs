@@ -1,10 +1,13 @@
 use bevy::prelude::*;
 
+use crate::actions::{Action, Actions};
+use crate::settings::Settings;
 use crate::states::{AnimationState, FadeState, GameState};
 
 pub mod board_renderer;
 mod fx;
 pub mod item_renderer;
+pub mod palette;
 pub mod sprites;
 pub mod unit_renderer;
 mod utils;
@@ -15,11 +18,25 @@ pub const MAP_Z: f32 = 0.;
 pub const MASK_Z: f32 = 1.;
 pub const UNIT_Z: f32 = 10.;
 pub const ITEM_Z: f32 = 5.;
+pub const FX_Z: f32 = 50.;
 pub const CURSOR_Z: f32 = 100.;
 pub const OVERLAY_Z: f32 = 200.;
 
 pub const UNIT_SPEED: f32 = 20.;
 
+// how fast the board animates in the current frame
+pub struct PlaybackSpeed {
+    pub multiplier: f32,
+    // units jump straight to their positions
+    pub instant: bool
+}
+
+impl Default for PlaybackSpeed {
+    fn default() -> Self {
+        PlaybackSpeed { multiplier: 1., instant: false }
+    }
+}
+
 pub struct GraphicsPlugin;
 
 impl Plugin for GraphicsPlugin {
@@ -29,6 +46,7 @@ impl Plugin for GraphicsPlugin {
                 .with_system(board_renderer::load_assets)
                 .with_system(unit_renderer::load_assets)
                 .with_system(item_renderer::load_assets)
+                .with_system(fx::load_assets)
         );
         app.add_system_set(
             SystemSet::on_exit(GameState::Spawning)
@@ -36,9 +54,18 @@ impl Plugin for GraphicsPlugin {
                 .with_system(unit_renderer::draw_units)
                 .with_system(item_renderer::draw_items)
         );
+        // items can be dropped on the board during the player's turn,
+        // an undo respawns the units and the items
+        app.add_system_set(
+            SystemSet::on_update(GameState::PlayerTurn)
+                .with_system(item_renderer::draw_items)
+                .with_system(unit_renderer::draw_units)
+        );
+        app.init_resource::<PlaybackSpeed>();
+        app.add_system(update_playback_speed);
         app.add_system_set(
             SystemSet::on_update(AnimationState::Animating)
-                .with_system(unit_renderer::animate_units)
+                .with_system(unit_renderer::animate_units.after(update_playback_speed))
         );
         app.add_system_set(
             SystemSet::on_exit(GameState::Spawning)
@@ -50,6 +77,22 @@ impl Plugin for GraphicsPlugin {
         app.add_system(
             unit_renderer::animate_sprites
         );
+        app.add_system(
+            unit_renderer::tint_units
+        );
+
+        // EFFECTS
+        app.add_system(fx::spawn_effects);
+        app.add_system(fx::animate_lunges.after(update_playback_speed));
+        app.add_system(fx::animate_effects.after(update_playback_speed));
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MainMenu)
+                .with_system(fx::clear_effects)
+        );
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MapGenerate)
+                .with_system(fx::clear_effects)
+        );
 
         // FADE
         app.add_system_set(
@@ -71,6 +114,23 @@ impl Plugin for GraphicsPlugin {
     }
 }
 
+fn update_playback_speed(
+    settings: Res<Settings>,
+    actions: Res<Actions>,
+    game_state: Res<State<GameState>>,
+    mut playback: ResMut<PlaybackSpeed>
+) {
+    let multiplier = match actions.pressed(Action::FastForward) {
+        true => settings.animation_speed * settings.fast_forward_speed,
+        false => settings.animation_speed
+    };
+    let instant = settings.instant_npc_moves && game_state.current() == &GameState::NPCTurn;
+    // only written on change, so the change detection stays meaningful
+    if playback.multiplier != multiplier || playback.instant != instant {
+        *playback = PlaybackSpeed { multiplier, instant };
+    }
+}
+
 pub fn camera_center(
     mut camera_query: Query<&mut Transform, (Without<crate::units::player::Player>, With<crate::camera::MainCamera>)>
 ) {
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::units::status::StatusKind;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaletteKind {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast
+}
+
+pub fn get_palette_kinds() -> Vec<PaletteKind> {
+    vec![
+        PaletteKind::Default,
+        PaletteKind::Deuteranopia,
+        PaletteKind::Protanopia,
+        PaletteKind::Tritanopia,
+        PaletteKind::HighContrast
+    ]
+}
+
+pub fn get_palette_name(kind: PaletteKind) -> &'static str {
+    match kind {
+        PaletteKind::Default => "Default",
+        PaletteKind::Deuteranopia => "Deuteranopia",
+        PaletteKind::Protanopia => "Protanopia",
+        PaletteKind::Tritanopia => "Tritanopia",
+        PaletteKind::HighContrast => "High contrast"
+    }
+}
+
+// every color that carries meaning on the board or in the ui
+pub struct Palette {
+    pub kind: PaletteKind,
+    pub cursor: Color,
+    pub capture: Color,
+    pub npc_cursor: Color,
+    pub target: Color,
+    // scaled by the number of attackers
+    pub danger: Color,
+    pub button: Color,
+    pub button_click: Color,
+    pub button_text: Color,
+    pub status: Color,
+    pub stunned: Color,
+    pub hasted: Color,
+    pub slowed: Color,
+    pub rooted: Color,
+    pub poisoned: Color
+}
+
+impl Palette {
+    pub fn status_color(&self, kind: StatusKind) -> Color {
+        match kind {
+            StatusKind::Stunned => self.stunned,
+            StatusKind::Hasted => self.hasted,
+            StatusKind::Slowed => self.slowed,
+            StatusKind::Rooted => self.rooted,
+            StatusKind::Poisoned => self.poisoned
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self { get_palette(PaletteKind::Default) }
+}
+
+pub fn get_palette(kind: PaletteKind) -> Palette {
+    let base = Palette {
+        kind,
+        cursor: Color::rgb(0.84, 0.85, 0.84),
+        capture: Color::rgb(0.84, 0.85, 0.84),
+        npc_cursor: Color::rgba(0.75, 0.33, 0.25, 0.75),
+        target: Color::rgba(0.45, 0.7, 0.85, 0.75),
+        danger: Color::rgb(0.75, 0.33, 0.25),
+        button: Color::WHITE,
+        button_click: Color::GRAY,
+        button_text: Color::rgb(0.27, 0.22, 0.19),
+        status: Color::rgb(0.77, 0.67, 0.58),
+        stunned: Color::SILVER,
+        hasted: Color::rgb(1., 0.9, 0.5),
+        slowed: Color::rgb(0.6, 0.7, 1.),
+        rooted: Color::rgb(0.6, 0.9, 0.5),
+        poisoned: Color::rgb(0.8, 0.5, 0.9)
+    };
+    match kind {
+        PaletteKind::Default => base,
+        // red and green are hard to tell apart - threats are orange, help is blue
+        PaletteKind::Deuteranopia => Palette {
+            capture: Color::rgb(0.35, 0.7, 1.),
+            npc_cursor: Color::rgba(0.9, 0.6, 0., 0.75),
+            target: Color::rgba(0., 0.45, 0.7, 0.85),
+            danger: Color::rgb(0.9, 0.6, 0.),
+            rooted: Color::rgb(0.35, 0.7, 1.),
+            poisoned: Color::rgb(0.8, 0.47, 0.65),
+            ..base
+        },
+        // reds look dark - threats are a bright yellow
+        PaletteKind::Protanopia => Palette {
+            capture: Color::rgb(0.35, 0.7, 1.),
+            npc_cursor: Color::rgba(0.95, 0.9, 0.25, 0.8),
+            target: Color::rgba(0., 0.45, 0.7, 0.85),
+            danger: Color::rgb(0.95, 0.9, 0.25),
+            rooted: Color::rgb(0.35, 0.7, 1.),
+            poisoned: Color::rgb(0.8, 0.47, 0.65),
+            ..base
+        },
+        // blue and yellow are hard to tell apart - threats are red, help is teal
+        PaletteKind::Tritanopia => Palette {
+            capture: Color::rgb(0., 0.75, 0.7),
+            npc_cursor: Color::rgba(0.85, 0.2, 0.3, 0.8),
+            target: Color::rgba(0., 0.6, 0.55, 0.85),
+            danger: Color::rgb(0.85, 0.2, 0.3),
+            hasted: Color::rgb(1., 0.6, 0.7),
+            slowed: Color::rgb(0., 0.75, 0.7),
+            ..base
+        },
+        PaletteKind::HighContrast => Palette {
+            cursor: Color::WHITE,
+            capture: Color::YELLOW,
+            npc_cursor: Color::RED,
+            target: Color::CYAN,
+            danger: Color::RED,
+            button_click: Color::YELLOW,
+            button_text: Color::BLACK,
+            status: Color::WHITE,
+            stunned: Color::DARK_GRAY,
+            hasted: Color::YELLOW,
+            slowed: Color::CYAN,
+            rooted: Color::GREEN,
+            poisoned: Color::FUCHSIA,
+            ..base
+        }
+    }
+}
@@ -1,16 +1,24 @@
 use bevy::prelude::*;
+use bevy::render::{
+    render_resource::{Extent3d, TextureDimension},
+    texture::TextureFormatPixelInfo
+};
 
-use crate::units::{Unit, UnitKind, UnitState};
+use crate::units::{Unit, UnitKind};
 use crate::board::Position;
-use crate::states::AnimationState;
+use crate::settings::Settings;
+use crate::states::{AnimationState, GameState};
 
-use super::{TILE_SIZE, UNIT_Z, UNIT_SPEED};
+use super::palette::Palette;
+use super::{PlaybackSpeed, TILE_SIZE, UNIT_Z, UNIT_SPEED};
+
+const SPRITE_PERIOD: f32 = 0.5;
 
 pub struct SpriteTimer(Timer);
 
 impl SpriteTimer {
     pub fn new() -> SpriteTimer {
-        SpriteTimer(Timer::from_seconds(0.5, true))
+        SpriteTimer(Timer::from_seconds(SPRITE_PERIOD, true))
     }
 }
 
@@ -23,28 +31,45 @@ pub struct UnitRenderer {
 
 pub fn animate_sprites(
     time: Res<Time>,
+    game_state: Res<State<GameState>>,
+    settings: Res<Settings>,
     mut timer: ResMut<SpriteTimer>,
-    mut query: Query<(&Unit, &mut UnitRenderer, &mut TextureAtlasSprite)>
+    mut query: Query<(&mut UnitRenderer, &mut TextureAtlasSprite)>
 ) {
+    if settings.is_changed() {
+        timer.0.set_duration(std::time::Duration::from_secs_f32(SPRITE_PERIOD / settings.animation_speed));
+    }
+    if game_state.current() == &GameState::Paused { return; }
     timer.0.tick(time.delta());
     if timer.0.just_finished() {
-        for (unit, mut renderer, mut sprite) in query.iter_mut() {
+        for (mut renderer, mut sprite) in query.iter_mut() {
             renderer.frame = (renderer.frame + 1) % renderer.frame_count;
             sprite.index = renderer.frame + renderer.base_sprite_idx;
-
-            sprite.color = match unit.state {
-                UnitState::Active => Color::WHITE,
-                UnitState::Paused => Color::SILVER
-            }
         }
     }
 }
 
+pub fn tint_units(
+    mut query: Query<(&Unit, &mut TextureAtlasSprite, ChangeTrackers<Unit>)>,
+    palette: Res<Palette>
+) {
+    for (unit, mut sprite, tracker) in query.iter_mut() {
+        if !tracker.is_changed() && !palette.is_changed() { continue; }
+        sprite.color = match unit.effects.strongest() {
+            Some(kind) => palette.status_color(kind),
+            None => Color::WHITE
+        };
+    }
+}
+
 pub fn animate_units(
     mut unit_query: Query<(&Position, &mut Transform)>,
     time: Res<Time>,
+    playback: Res<PlaybackSpeed>,
+    game_state: Res<State<GameState>>,
     mut animation_state: ResMut<State<AnimationState>>
 ) {
+    if game_state.current() == &GameState::Paused { return; }
     let mut animating = false;
     for (position, mut transform) in unit_query.iter_mut() {
         let target = Vec3::new(
@@ -53,9 +78,13 @@ pub fn animate_units(
             UNIT_Z
         );
         if (target-transform.translation).length() > 0.1 {
+            if playback.instant {
+                transform.translation = target;
+                continue;
+            }
             transform.translation = transform.translation.lerp(
                 target,
-                UNIT_SPEED * time.delta_seconds()
+                (UNIT_SPEED * playback.multiplier * time.delta_seconds()).min(1.)
             );
             animating = true;
         }
@@ -127,7 +156,37 @@ pub fn load_assets(
     commands.insert_resource(UnitSprites(atlas_handle));
 }
 
-fn get_sprite_idx(kind: &UnitKind) -> usize {
+// ui nodes can't use texture atlases, so the unit's frame is copied into its own image
+pub fn get_unit_image(
+    kind: &UnitKind,
+    images: &mut Assets<Image>,
+    atlases: &Assets<TextureAtlas>,
+    sprites: &UnitSprites
+) -> Option<Handle<Image>> {
+    let atlas = atlases.get(&sprites.0)?;
+    let rect = atlas.textures.get(get_sprite_idx(kind))?;
+    let source = images.get(&atlas.texture)?;
+
+    let format = source.texture_descriptor.format;
+    let row = source.texture_descriptor.size.width as usize * format.pixel_size();
+    let (x, y) = (rect.min.x as usize * format.pixel_size(), rect.min.y as usize);
+    let (width, height) = (rect.width() as usize, rect.height() as usize);
+
+    let mut data = Vec::with_capacity(width * height * format.pixel_size());
+    for line in y..y + height {
+        let start = line * row + x;
+        data.extend_from_slice(&source.data[start..start + width * format.pixel_size()]);
+    }
+    let image = Image::new(
+        Extent3d { width: width as u32, height: height as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        format
+    );
+    Some(images.add(image))
+}
+
+pub fn get_sprite_idx(kind: &UnitKind) -> usize {
     match kind {
         UnitKind::Player => 0,
         UnitKind::Ram => 2,
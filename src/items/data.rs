@@ -1,20 +1,216 @@
-use rand::Rng;
+use rand::{Rng, prelude::SliceRandom};
 
-use super::ItemKind;
+use crate::units::status::StatusKind;
+
+use super::{ItemKind, Relic};
+
+// effects are expanded into commands at the moment the item is used
+#[derive(Clone, Copy, Debug)]
+pub enum ItemEffect {
+    AddAP(u8),
+    RemoveAP,
+    // move the user to the target tile
+    Teleport,
+    // swap places with the target npc
+    SwapWithEnemy,
+    // pause the target npc
+    FreezeTarget,
+    // pause every npc on the board
+    FreezeAll,
+    // kill every npc within the range around the user
+    Bomb(u8),
+    // show npc moves until the end of the level
+    RevealIntents,
+    // add the target npc's pattern to the hand, without capturing it
+    CopyPattern,
+    // walk through walls until the end of the turn
+    PhaseWalls,
+    // status for the given number of turns on the user
+    StatusSelf(StatusKind, u8),
+    // status for the given number of turns on the target npc
+    StatusTarget(StatusKind, u8)
+}
+
+// what has to be picked on the board before the item can be used
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemTarget {
+    None,
+    FreeTile,
+    Enemy
+}
+
+pub fn get_item_effects(kind: ItemKind) -> Vec<ItemEffect> {
+    match kind {
+        ItemKind::SpeedMushroom => vec![ItemEffect::AddAP(1)],
+        ItemKind::StopMushroom => vec![ItemEffect::RemoveAP],
+        ItemKind::Teleport => vec![ItemEffect::Teleport],
+        ItemKind::Swap => vec![ItemEffect::SwapWithEnemy],
+        ItemKind::Freeze => vec![ItemEffect::FreezeAll],
+        ItemKind::Frost => vec![ItemEffect::FreezeTarget],
+        ItemKind::Bomb => vec![ItemEffect::Bomb(1)],
+        ItemKind::Eye => vec![ItemEffect::RevealIntents],
+        ItemKind::Ghost => vec![ItemEffect::PhaseWalls],
+        ItemKind::Coffee => vec![ItemEffect::StatusSelf(StatusKind::Hasted, 3)],
+        ItemKind::Snail => vec![ItemEffect::StatusTarget(StatusKind::Slowed, 4)],
+        ItemKind::Vine => vec![ItemEffect::StatusTarget(StatusKind::Rooted, 3)],
+        ItemKind::Venom => vec![ItemEffect::StatusTarget(StatusKind::Poisoned, 3)],
+        ItemKind::Mirror => vec![ItemEffect::CopyPattern],
+        // passive items work through their relic (or the armor count) instead
+        ItemKind::Armor | ItemKind::Wings | ItemKind::Spring | ItemKind::Crown
+            | ItemKind::Machete | ItemKind::Backpack => Vec::new()
+    }
+}
+
+pub fn get_item_relic(kind: ItemKind) -> Option<Relic> {
+    match kind {
+        ItemKind::Wings => Some(Relic::ExtraAP),
+        ItemKind::Spring => Some(Relic::ExtraSquare),
+        ItemKind::Crown => Some(Relic::FreeCapture),
+        ItemKind::Machete => Some(Relic::BushImmunity),
+        ItemKind::Backpack => Some(Relic::ExtraSlot),
+        ItemKind::SpeedMushroom | ItemKind::StopMushroom | ItemKind::Armor | ItemKind::Teleport
+            | ItemKind::Swap | ItemKind::Freeze | ItemKind::Bomb | ItemKind::Eye | ItemKind::Ghost
+            | ItemKind::Frost | ItemKind::Coffee | ItemKind::Snail | ItemKind::Vine
+            | ItemKind::Venom | ItemKind::Mirror => None
+    }
+}
+
+pub fn get_item_target(kind: ItemKind) -> ItemTarget {
+    match kind {
+        ItemKind::Teleport => ItemTarget::FreeTile,
+        ItemKind::Swap | ItemKind::Frost | ItemKind::Snail | ItemKind::Vine
+            | ItemKind::Venom | ItemKind::Mirror => ItemTarget::Enemy,
+        ItemKind::SpeedMushroom | ItemKind::StopMushroom | ItemKind::Armor | ItemKind::Freeze
+            | ItemKind::Bomb | ItemKind::Eye | ItemKind::Ghost | ItemKind::Wings | ItemKind::Spring
+            | ItemKind::Crown | ItemKind::Machete | ItemKind::Backpack | ItemKind::Coffee => ItemTarget::None
+    }
+}
+
+pub fn get_item_label(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::SpeedMushroom => "+1",
+        ItemKind::StopMushroom => "0",
+        ItemKind::Teleport => "TP",
+        ItemKind::Swap => "<>",
+        ItemKind::Freeze => "ICE",
+        ItemKind::Frost => "1x",
+        ItemKind::Bomb => "3x3",
+        ItemKind::Eye => "EYE",
+        ItemKind::Ghost => "WALL",
+        ItemKind::Coffee => "+1x3",
+        ItemKind::Snail => "1/2",
+        ItemKind::Vine => "ROOT",
+        ItemKind::Venom => "POI",
+        ItemKind::Mirror => "COPY",
+        // passive items are shown in the status menu, their icon is enough
+        ItemKind::Armor | ItemKind::Wings | ItemKind::Spring | ItemKind::Crown
+            | ItemKind::Machete | ItemKind::Backpack => ""
+    }
+}
+
+pub fn get_item_description(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::SpeedMushroom => "+1 AP",
+        ItemKind::StopMushroom => "ends your turn",
+        ItemKind::Armor => "blocks a single capture",
+        ItemKind::Teleport => "jump to any free tile",
+        ItemKind::Swap => "swap places with an enemy",
+        ItemKind::Freeze => "every enemy loses a turn",
+        ItemKind::Frost => "an enemy loses a turn",
+        ItemKind::Bomb => "removes enemies around you",
+        ItemKind::Eye => "shows enemy moves for the level",
+        ItemKind::Ghost => "walk through walls this turn",
+        ItemKind::Wings => "+1 AP every turn",
+        ItemKind::Spring => "an extra square in your pattern",
+        ItemKind::Crown => "first capture each turn gives an extra AP",
+        ItemKind::Machete => "bushes don't stop you",
+        ItemKind::Backpack => "an extra inventory slot",
+        ItemKind::Coffee => "+1 AP for your next 3 turns",
+        ItemKind::Snail => "an enemy acts every other turn",
+        ItemKind::Vine => "an enemy can only capture for 3 turns",
+        ItemKind::Venom => "an enemy dies in 3 turns",
+        ItemKind::Mirror => "learn an enemy's moves"
+    }
+}
 
 pub fn is_passive(kind: ItemKind) -> bool {
+    matches!(kind, ItemKind::Armor) || get_item_relic(kind).is_some()
+}
+
+pub fn get_item_types() -> Vec<ItemKind> {
+    vec![
+        ItemKind::SpeedMushroom,
+        ItemKind::StopMushroom,
+        ItemKind::Armor,
+        ItemKind::Teleport,
+        ItemKind::Swap,
+        ItemKind::Freeze,
+        ItemKind::Frost,
+        ItemKind::Bomb,
+        ItemKind::Eye,
+        ItemKind::Ghost,
+        ItemKind::Wings,
+        ItemKind::Spring,
+        ItemKind::Crown,
+        ItemKind::Machete,
+        ItemKind::Backpack,
+        ItemKind::Coffee,
+        ItemKind::Snail,
+        ItemKind::Vine,
+        ItemKind::Venom,
+        ItemKind::Mirror
+    ]
+}
+
+pub fn get_item_price(kind: ItemKind) -> u32 {
     match kind {
-        ItemKind::Armor => true,
-        ItemKind::SpeedMushroom => false,
-        ItemKind::StopMushroom => false
+        ItemKind::SpeedMushroom => 2,
+        ItemKind::StopMushroom => 1,
+        ItemKind::Armor => 5,
+        ItemKind::Bomb => 4,
+        ItemKind::Freeze => 4,
+        ItemKind::Venom => 5,
+        kind if get_item_relic(kind).is_some() => 8,
+        _ => 3
     }
 }
 
-pub fn get_random_kind() -> ItemKind {
-    let mut rng = rand::thread_rng();
-    match rng.gen_range(0.0..1.0) {
-        a if a < 0.1 => ItemKind::Armor,
-        a if a < 0.45 => ItemKind::StopMushroom,
-        _ => ItemKind::SpeedMushroom
+fn get_item_weight(kind: ItemKind) -> u32 {
+    match kind {
+        ItemKind::SpeedMushroom => 20,
+        ItemKind::StopMushroom => 20,
+        ItemKind::Armor => 10,
+        ItemKind::Teleport => 10,
+        ItemKind::Swap => 10,
+        ItemKind::Freeze => 5,
+        ItemKind::Frost => 5,
+        ItemKind::Bomb => 6,
+        ItemKind::Eye => 7,
+        ItemKind::Ghost => 7,
+        ItemKind::Wings => 2,
+        ItemKind::Spring => 3,
+        ItemKind::Crown => 3,
+        ItemKind::Machete => 3,
+        ItemKind::Backpack => 3,
+        ItemKind::Coffee => 8,
+        ItemKind::Snail => 7,
+        ItemKind::Vine => 7,
+        ItemKind::Venom => 4,
+        ItemKind::Mirror => 7
     }
-}
\ No newline at end of file
+}
+
+pub fn get_random_kind(rng: &mut impl Rng) -> ItemKind {
+    *get_item_types()
+        .choose_weighted(rng, |k| get_item_weight(*k))
+        .unwrap()
+}
+
+// relics and armor don't take an inventory slot
+pub fn get_random_passive_kind(rng: &mut impl Rng) -> ItemKind {
+    let kinds: Vec<ItemKind> = get_item_types().into_iter()
+        .filter(|k| is_passive(*k))
+        .collect();
+    *kinds.choose_weighted(rng, |k| get_item_weight(*k))
+        .unwrap()
+}
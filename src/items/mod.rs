@@ -1,19 +1,28 @@
 use bevy::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::board::{
     Blocker, Board, Position,
     utils::get_spawn_position
 };
 use crate::command::{CommandEvent, CommandType};
+use crate::events::GameEvent;
+use crate::run::RunRng;
 use crate::units::{
+    npc::NPC,
     player::{Player, PlayerData},
+    status::{StatusEffect, StatusKind},
     Unit
 };
 use crate::ui::RedrawUIEvent;
 use crate::states::GameState;
+use crate::vectors::Vector2Int;
+
+use data::{ItemEffect, ItemTarget};
 
 pub mod data;
+pub mod shop;
 
 pub struct ItemsPlugin;
 
@@ -22,8 +31,21 @@ const ITEM_CHANCE: f32 = 0.5;
 impl Plugin for ItemsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<UseItemEvent>();
+        app.add_event::<TargetingEvent>();
+        app.add_event::<InventoryEvent>();
+        app.init_resource::<ItemTargeting>();
+        app.init_resource::<InventoryPrompt>();
+        app.add_event::<shop::InterludeEvent>();
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Interlude)
+                .with_system(shop::start_interlude)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Interlude)
+                .with_system(shop::handle_interlude)
+        );
         app.add_system_set(
-            SystemSet::on_exit(GameState::GameOver)
+            SystemSet::on_enter(GameState::MainMenu)
                 .with_system(clear_items)
         );
         app.add_system_set(
@@ -33,19 +55,57 @@ impl Plugin for ItemsPlugin {
         app.add_system_set(
             SystemSet::on_enter(GameState::MapGenerate)
                 .with_system(clear_items)
+                .with_system(clear_prompt)
         );
         app.add_system_set(
             SystemSet::on_update(GameState::PlayerTurn)
                 .with_system(use_item)
+                .with_system(manage_inventory)
+        );
+        app.add_system_set(
+            SystemSet::on_enter(GameState::PlayerTurn)
+                .with_system(reset_targeting)
         );
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ItemKind {
     SpeedMushroom,
     StopMushroom,
-    Armor
+    Armor,
+    Teleport,
+    Swap,
+    Freeze,
+    Bomb,
+    Eye,
+    Ghost,
+    Frost,
+    Wings,
+    Spring,
+    Crown,
+    Machete,
+    Backpack,
+    Coffee,
+    Snail,
+    Vine,
+    Venom,
+    Mirror
+}
+
+// persistent modifiers granted by passive items
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Relic {
+    // +1 AP at the start of each turn
+    ExtraAP,
+    // an extra square in the base move pattern
+    ExtraSquare,
+    // every capture already refunds its AP, so the first capture in a turn gives one on top
+    FreeCapture,
+    // bushes do not pause the player
+    BushImmunity,
+    // an extra inventory slot
+    ExtraSlot
 }
 
 #[derive(Clone, Component)]
@@ -56,19 +116,20 @@ pub struct Item {
 fn spawn_items(
     mut commands: Commands,
     board_query: Query<&Board>,
-    blocker_query: Query<&Position, With<Blocker>>
+    blocker_query: Query<&Position, With<Blocker>>,
+    mut run_rng: ResMut<RunRng>
 ) {
     let board = board_query.get_single().unwrap();
-    let mut rng = rand::thread_rng();
+    let rng = &mut run_rng.rng;
     if rng.gen_range(0.0..1.0) > ITEM_CHANCE { return; }
 
-    let kind = data::get_random_kind();
+    let kind = data::get_random_kind(rng);
 
     let blocker_positions = blocker_query.iter()
         .map(|a| a.v)
         .collect();
 
-    if let Some(position) = get_spawn_position(&blocker_positions, &board) {
+    if let Some(position) = get_spawn_position(&blocker_positions, &board, rng) {
         commands.spawn()
             .insert(Position { v: position })
             .insert(Item { kind });
@@ -86,28 +147,298 @@ fn clear_items(
 
 pub struct UseItemEvent(pub usize);
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetingEvent {
+    Select(Vector2Int),
+    Confirm,
+    Cancel
+}
+
+// an item waiting for its board target
+#[derive(Default)]
+pub struct ItemTargeting {
+    pub item: Option<usize>,
+    pub target: Option<Vector2Int>
+}
+
+impl ItemTargeting {
+    pub fn is_active(&self) -> bool {
+        self.item.is_some()
+    }
+    fn clear(&mut self) {
+        self.item = None;
+        self.target = None;
+    }
+}
+
+pub enum InventoryEvent {
+    // swap the slot with the item waiting in the prompt
+    Swap(usize),
+    Discard(usize),
+    MoveLeft(usize),
+    // leave the prompted item on the ground
+    Leave
+}
+
+// a consumable that could not be picked up because the inventory is full
+#[derive(Default)]
+pub struct InventoryPrompt {
+    pub item: Option<Entity>
+}
+
+fn clear_prompt(
+    mut prompt: ResMut<InventoryPrompt>
+) {
+    prompt.item = None;
+}
+
+fn manage_inventory(
+    mut commands: Commands,
+    mut ev_inventory: EventReader<InventoryEvent>,
+    mut ev_ui: EventWriter<RedrawUIEvent>,
+    mut player_data: ResMut<PlayerData>,
+    mut prompt: ResMut<InventoryPrompt>,
+    mut targeting: ResMut<ItemTargeting>,
+    item_query: Query<(&Item, &Position)>
+) {
+    for ev in ev_inventory.iter() {
+        let len = player_data.items.len();
+        match *ev {
+            InventoryEvent::Swap(idx) => {
+                let entity = match prompt.item {
+                    Some(e) => e,
+                    None => continue
+                };
+                if idx >= len { continue; }
+                if let Ok((item, position)) = item_query.get(entity) {
+                    let dropped = std::mem::replace(&mut player_data.items[idx], item.clone());
+                    commands.entity(entity).despawn_recursive();
+                    commands.spawn()
+                        .insert(Position { v: position.v })
+                        .insert(dropped);
+                }
+                prompt.item = None;
+            },
+            InventoryEvent::Discard(idx) => {
+                if idx >= len { continue; }
+                player_data.items.remove(idx);
+            },
+            InventoryEvent::MoveLeft(idx) => {
+                if idx >= len || len < 2 { continue; }
+                match idx {
+                    0 => player_data.items.rotate_left(1),
+                    _ => player_data.items.swap(idx, idx - 1)
+                }
+            },
+            InventoryEvent::Leave => prompt.item = None
+        }
+        // slot indices might have changed
+        targeting.clear();
+        ev_ui.send(RedrawUIEvent);
+    }
+}
+
+fn reset_targeting(
+    mut targeting: ResMut<ItemTargeting>
+) {
+    targeting.clear();
+}
+
+struct ItemContext {
+    entity: Entity,
+    source: Vector2Int,
+    npcs: Vec<(Entity, Vector2Int)>,
+    blocker_positions: Vec<Vector2Int>
+}
+
 pub fn use_item(
     mut ev_use_item: EventReader<UseItemEvent>,
+    mut ev_targeting: EventReader<TargetingEvent>,
     mut ev_ui: EventWriter<RedrawUIEvent>,
     mut ev_command: EventWriter<CommandEvent>,
+    mut ev_game: EventWriter<GameEvent>,
     mut player_data: ResMut<PlayerData>,
-    player_query: Query<Entity, With<Player>>
+    mut targeting: ResMut<ItemTargeting>,
+    player_query: Query<(Entity, &Position), With<Player>>,
+    npc_query: Query<(Entity, &Position), With<NPC>>,
+    blocker_query: Query<&Position, With<Blocker>>,
+    board_query: Query<&Board>
 ) {
+    let (entity, position) = match player_query.get_single() {
+        Ok(p) => p,
+        Err(_) => return
+    };
+    let board = match board_query.get_single() {
+        Ok(b) => b,
+        Err(_) => return
+    };
+    let context = ItemContext {
+        entity,
+        source: position.v,
+        npcs: npc_query.iter().map(|(e, p)| (e, p.v)).collect(),
+        blocker_positions: blocker_query.iter().map(|p| p.v).collect()
+    };
+
     for ev in ev_use_item.iter() {
-        if let Ok(entity) = player_query.get_single() {
-            if let Some(item) = player_data.items.get(ev.0) {
-                match item.kind {
-                    ItemKind::SpeedMushroom => {
-                        ev_command.send(CommandEvent(CommandType::AddAP(entity, 1)))
-                    },
-                    ItemKind::StopMushroom => {
-                        ev_command.send(CommandEvent(CommandType::RemoveAP(entity)))
-                    },
-                    _ => ()
+        let kind = match player_data.items.get(ev.0) {
+            Some(item) => item.kind,
+            None => continue
+        };
+        if targeting.item == Some(ev.0) {
+            // clicking the pending item again cancels the selection
+            targeting.clear();
+        } else {
+            match data::get_item_target(kind) {
+                ItemTarget::None => {
+                    targeting.clear();
+                    apply_item(ev.0, None, &context, &mut player_data, &mut ev_command, &mut ev_game);
+                },
+                _ => {
+                    targeting.item = Some(ev.0);
+                    targeting.target = None;
+                }
+            }
+        }
+        ev_ui.send(RedrawUIEvent);
+    }
+
+    for ev in ev_targeting.iter() {
+        let idx = match targeting.item {
+            Some(idx) => idx,
+            None => continue
+        };
+        let kind = match player_data.items.get(idx) {
+            Some(item) => item.kind,
+            None => {
+                targeting.clear();
+                continue
+            }
+        };
+        let confirmed = match ev {
+            TargetingEvent::Select(v) => {
+                let targets = get_legal_targets(
+                    kind,
+                    &context.npcs,
+                    &context.blocker_positions,
+                    board
+                );
+                if !targets.contains(v) { continue; }
+                // selecting the same tile twice confirms it
+                if targeting.target == Some(*v) {
+                    true
+                } else {
+                    targeting.target = Some(*v);
+                    false
                 }
-                player_data.items.remove(ev.0);                                
-                ev_ui.send(RedrawUIEvent);
+            },
+            TargetingEvent::Confirm => targeting.target.is_some(),
+            TargetingEvent::Cancel => {
+                targeting.clear();
+                false
+            }
+        };
+        if confirmed {
+            apply_item(idx, targeting.target, &context, &mut player_data, &mut ev_command, &mut ev_game);
+            targeting.clear();
+        }
+        ev_ui.send(RedrawUIEvent);
+    }
+}
+
+pub fn get_legal_targets(
+    kind: ItemKind,
+    npcs: &[(Entity, Vector2Int)],
+    blocker_positions: &[Vector2Int],
+    board: &Board
+) -> Vec<Vector2Int> {
+    match data::get_item_target(kind) {
+        ItemTarget::None => Vec::new(),
+        ItemTarget::FreeTile => board.tiles.keys()
+            .filter(|v| !blocker_positions.contains(v))
+            .copied()
+            .collect(),
+        ItemTarget::Enemy => npcs.iter()
+            .map(|(_, v)| *v)
+            .collect()
+    }
+}
+
+fn apply_item(
+    idx: usize,
+    target: Option<Vector2Int>,
+    context: &ItemContext,
+    player_data: &mut PlayerData,
+    ev_command: &mut EventWriter<CommandEvent>,
+    ev_game: &mut EventWriter<GameEvent>
+) {
+    let item = player_data.items.remove(idx);
+    player_data.used_item = true;
+    ev_game.send(GameEvent::ItemUsed { kind: item.kind });
+    for effect in data::get_item_effects(item.kind) {
+        for command in get_effect_commands(effect, target, context) {
+            ev_command.send(CommandEvent(command));
+        }
+    }
+}
+
+fn get_effect_commands(
+    effect: ItemEffect,
+    target: Option<Vector2Int>,
+    context: &ItemContext
+) -> Vec<CommandType> {
+    let entity = context.entity;
+    let target_npc = context.npcs.iter()
+        .find(|(_, v)| Some(*v) == target)
+        .map(|(e, _)| *e);
+
+    match effect {
+        ItemEffect::AddAP(ap) => vec![CommandType::AddAP(entity, ap)],
+        ItemEffect::RemoveAP => vec![CommandType::RemoveAP(entity)],
+        ItemEffect::Teleport => {
+            match target {
+                Some(v) => vec![CommandType::Teleport(entity, v)],
+                None => Vec::new()
+            }
+        },
+        ItemEffect::SwapWithEnemy => {
+            match target_npc {
+                Some(npc) => vec![CommandType::SwapUnits(entity, npc)],
+                None => Vec::new()
+            }
+        },
+        ItemEffect::FreezeTarget => {
+            match target_npc {
+                Some(npc) => vec![CommandType::ApplyStatus(npc, StatusEffect::new(StatusKind::Stunned, 1))],
+                None => Vec::new()
+            }
+        },
+        ItemEffect::FreezeAll => {
+            context.npcs.iter()
+                .map(|(npc, _)| CommandType::ApplyStatus(*npc, StatusEffect::new(StatusKind::Stunned, 1)))
+                .collect()
+        },
+        ItemEffect::Bomb(range) => {
+            let range = range as i32;
+            let source = context.source;
+            context.npcs.iter()
+                .filter(|(_, v)| (v.x - source.x).abs() <= range && (v.y - source.y).abs() <= range)
+                .map(|(npc, _)| CommandType::KillUnit(*npc))
+                .collect()
+        },
+        ItemEffect::RevealIntents => vec![CommandType::RevealIntents],
+        ItemEffect::CopyPattern => {
+            match target_npc {
+                Some(npc) => vec![CommandType::CopyPattern(npc)],
+                None => Vec::new()
+            }
+        },
+        ItemEffect::PhaseWalls => vec![CommandType::PhaseWalls],
+        ItemEffect::StatusSelf(kind, turns) => vec![CommandType::ApplyStatus(entity, StatusEffect::new(kind, turns))],
+        ItemEffect::StatusTarget(kind, turns) => {
+            match target_npc {
+                Some(npc) => vec![CommandType::ApplyStatus(npc, StatusEffect::new(kind, turns))],
+                None => Vec::new()
             }
         }
     }
-}
\ No newline at end of file
+}
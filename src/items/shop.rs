@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::command::{CommandEvent, CommandType};
+use crate::run::RunRng;
+use crate::states::GameState;
+use crate::ui::RedrawUIEvent;
+use crate::units::player::PlayerData;
+
+use super::{Item, ItemKind, data};
+
+const OFFER_COUNT: usize = 3;
+// every n-th level is followed by a shop instead of a reward choice
+const SHOP_INTERVAL: u32 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InterludeKind {
+    Reward,
+    Shop
+}
+
+pub struct Offer {
+    pub kind: ItemKind,
+    pub price: u32,
+    pub taken: bool
+}
+
+pub struct Interlude {
+    pub kind: InterludeKind,
+    pub offers: Vec<Offer>
+}
+
+pub enum InterludeEvent {
+    Take(usize),
+    Continue
+}
+
+pub fn start_interlude(
+    mut commands: Commands,
+    player_data: Res<PlayerData>,
+    mut run_rng: ResMut<RunRng>,
+    mut ev_ui: EventWriter<RedrawUIEvent>
+) {
+    let kind = match player_data.level % SHOP_INTERVAL {
+        0 => InterludeKind::Shop,
+        _ => InterludeKind::Reward
+    };
+    // a full inventory could not take a consumable, so none are offered
+    let full = player_data.items.len() >= player_data.max_items();
+    let offers = (0..OFFER_COUNT)
+        .map(|_| {
+            let item = match full {
+                true => data::get_random_passive_kind(&mut run_rng.rng),
+                false => data::get_random_kind(&mut run_rng.rng)
+            };
+            Offer {
+                kind: item,
+                price: match kind {
+                    InterludeKind::Shop => data::get_item_price(item),
+                    InterludeKind::Reward => 0
+                },
+                taken: false
+            }
+        })
+        .collect();
+
+    commands.insert_resource(Interlude { kind, offers });
+    ev_ui.send(RedrawUIEvent);
+}
+
+pub fn handle_interlude(
+    mut ev_interlude: EventReader<InterludeEvent>,
+    mut ev_ui: EventWriter<RedrawUIEvent>,
+    mut ev_command: EventWriter<CommandEvent>,
+    mut interlude: ResMut<Interlude>,
+    mut player_data: ResMut<PlayerData>,
+    mut game_state: ResMut<State<GameState>>
+) {
+    for ev in ev_interlude.iter() {
+        match *ev {
+            InterludeEvent::Take(idx) => {
+                let kind = interlude.kind;
+                let offer = match interlude.offers.get_mut(idx) {
+                    Some(o) => o,
+                    None => continue
+                };
+                if offer.taken || offer.price > player_data.coins { continue; }
+                if !give_item(offer.kind, &mut player_data, &mut ev_command) { continue; }
+
+                player_data.coins -= offer.price;
+                offer.taken = true;
+                ev_ui.send(RedrawUIEvent);
+
+                // only a single reward can be chosen
+                if kind == InterludeKind::Reward {
+                    let _ = game_state.set(GameState::MapGenerate);
+                    return;
+                }
+            },
+            InterludeEvent::Continue => {
+                let _ = game_state.set(GameState::MapGenerate);
+                return;
+            }
+        }
+    }
+}
+
+fn give_item(
+    kind: ItemKind,
+    player_data: &mut PlayerData,
+    ev_command: &mut EventWriter<CommandEvent>
+) -> bool {
+    if let Some(command) = get_passive_command(kind) {
+        ev_command.send(CommandEvent(command));
+        return true;
+    }
+    if player_data.items.len() >= player_data.max_items() { return false; }
+    player_data.items.push(Item { kind });
+    true
+}
+
+pub fn get_passive_command(kind: ItemKind) -> Option<CommandType> {
+    if !data::is_passive(kind) { return None; }
+    match data::get_item_relic(kind) {
+        Some(relic) => Some(CommandType::AddRelic(relic)),
+        None => Some(CommandType::AddArmor(1))
+    }
+}
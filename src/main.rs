@@ -2,27 +2,34 @@ use bevy::prelude::*;
 use bevy::render::texture::ImageSettings;
 // use bevy_inspector_egui::WorldInspectorPlugin;
 
+mod achievements;
+mod actions;
 mod assets;
 mod command;
 mod board;
 mod camera;
+mod codex;
+mod events;
 mod graphics;
 mod items;
 mod manager;
+mod profile;
+mod run;
+mod settings;
+mod sound;
+mod statistics;
 mod states;
+mod storage;
 mod ui;
+mod undo;
 mod units;
 mod vectors;
 
 fn main() {
     let mut app = App::new();
-    app.insert_resource(
-        WindowDescriptor {
-            height: 800.,
-            width: 800.,
-            ..Default::default()
-        }
-    );
+    let settings = settings::load_settings();
+    app.insert_resource(settings::get_window_descriptor(&settings));
+    app.insert_resource(settings);
     app.insert_resource(ImageSettings::default_nearest());
 
     app.add_plugins(DefaultPlugins);
@@ -38,6 +45,7 @@ fn main() {
             .with_system(assets::check_asset_loading)
     );
 
+    app.add_plugin(events::EventsPlugin);
     app.add_plugin(command::CommandPlugin);
     app.add_plugin(board::BoardPlugin);
     app.add_plugin(units::UnitsPlugin);
@@ -46,6 +54,15 @@ fn main() {
 
     app.add_plugin(ui::UIPlugin);
     app.add_plugin(manager::ManagerPlugin);
+    app.add_plugin(actions::ActionsPlugin);
+    app.add_plugin(profile::ProfilePlugin);
+    app.add_plugin(settings::SettingsPlugin);
+    app.add_plugin(achievements::AchievementsPlugin);
+    app.add_plugin(codex::CodexPlugin);
+    app.add_plugin(run::RunPlugin);
+    app.add_plugin(statistics::StatisticsPlugin);
+    app.add_plugin(sound::SoundPlugin);
+    app.add_plugin(undo::UndoPlugin);
 
     app.add_startup_system(camera::spawn_camera);
     app.run();
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 
+use crate::events::GameEvent;
 use crate::states::{FadeState, GameState};
-use crate::units::npc::NPC;
+use crate::units::{npc::NPC, player::PlayerData};
 
 pub struct ManagerPlugin;
 
@@ -16,10 +17,14 @@ impl Plugin for ManagerPlugin {
 
 fn next_level(
     mut fade_state: ResMut<State<FadeState>>,
+    mut ev_game: EventWriter<GameEvent>,
+    player_data: Res<PlayerData>,
     npc_query: Query<&NPC>
 ) {
     if npc_query.is_empty() {
         // game_state.set(GameState::MapGenerate);
-        fade_state.set(FadeState::In);
+        if fade_state.set(FadeState::In).is_ok() {
+            ev_game.send(GameEvent::LevelCleared { level: player_data.level });
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::achievements::Achievement;
+use crate::board::tile::TileKind;
+use crate::items::ItemKind;
+use crate::statistics::Statistics;
+use crate::units::UnitKind;
+use crate::storage;
+
+const PROFILE_FILE: &str = "profile.ron";
+
+// progress kept between runs
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub achievements: Vec<Achievement>,
+    // codex entries unlocked so far
+    pub seen_units: Vec<UnitKind>,
+    pub seen_items: Vec<ItemKind>,
+    pub seen_tiles: Vec<TileKind>,
+    pub stats: Statistics
+}
+
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        let profile: Profile = storage::load(PROFILE_FILE).unwrap_or_default();
+        app.insert_resource(profile);
+        app.add_system(save_profile);
+    }
+}
+
+fn save_profile(
+    profile: Res<Profile>
+) {
+    if profile.is_changed() && !profile.is_added() {
+        storage::save(PROFILE_FILE, &*profile);
+    }
+}
@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Blocker, Position};
+use crate::events::GameEvent;
+use crate::items::{Item, ItemKind, Relic};
+use crate::states::GameState;
+use crate::storage;
+use crate::ui::RedrawUIEvent;
+use crate::units::{
+    Unit,
+    UnitKind,
+    data::get_unit_behaviour,
+    npc::{NPC, SpawnOrder},
+    player::{MoveCard, Player, PlayerData},
+    status::{StatusEffect, StatusEffects}
+};
+use crate::vectors::Vector2Int;
+
+const RUN_FILE: &str = "run.ron";
+const MAX_RANDOM_SEED: u64 = 1_000_000_000;
+
+// every floor gets its own generator, so a continued run plays out the same
+pub struct RunRng {
+    pub seed: u64,
+    pub rng: StdRng
+}
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        RunRng { seed, rng: StdRng::seed_from_u64(seed) }
+    }
+    fn start_floor(&mut self, level: u32) {
+        self.rng = StdRng::seed_from_u64(self.seed.wrapping_add(level as u64));
+    }
+}
+
+// the run as it was when the current floor started
+#[derive(Serialize, Deserialize)]
+pub struct SavedRun {
+    pub seed: u64,
+    pub level: u32,
+    coins: u32,
+    armor: u8,
+    items: Vec<ItemKind>,
+    relics: Vec<Relic>,
+    hand: Vec<(UnitKind, u8)>,
+    #[serde(default)]
+    used_item: bool,
+    // where the floor was left with save & quit, restarting the floor ignores it
+    #[serde(default)]
+    pub floor: Option<FloorSnapshot>
+}
+
+impl SavedRun {
+    fn new(seed: u64, player_data: &PlayerData) -> Self {
+        SavedRun {
+            seed,
+            level: player_data.level,
+            coins: player_data.coins,
+            armor: player_data.armor,
+            items: player_data.items.iter().map(|i| i.kind).collect(),
+            relics: player_data.relics.clone(),
+            hand: get_saved_hand(player_data),
+            used_item: player_data.used_item,
+            floor: None
+        }
+    }
+    fn restore(&self) -> PlayerData {
+        let mut player_data = PlayerData {
+            level: self.level,
+            coins: self.coins,
+            armor: self.armor,
+            items: self.items.iter().map(|kind| Item { kind: *kind }).collect(),
+            relics: self.relics.clone(),
+            hand: restore_hand(&self.hand),
+            used_item: self.used_item,
+            ..Default::default()
+        };
+        // relics can change the base pattern
+        player_data.reset_behaviour();
+        player_data
+    }
+}
+
+// a continued run goes back to the saved floor once it is generated again
+#[derive(Default)]
+pub struct PendingFloor(pub Option<FloorSnapshot>);
+
+// a unit on the floor, its pattern comes back from the kind
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct SavedUnit {
+    kind: UnitKind,
+    v: Vector2Int,
+    ap: u8,
+    effects: Vec<StatusEffect>,
+    // None for the player
+    spawn_order: Option<usize>
+}
+
+// everything that changes while the floor is played,
+// the board itself comes back from the floor's seed
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FloorSnapshot {
+    level: u32,
+    coins: u32,
+    armor: u8,
+    items: Vec<ItemKind>,
+    relics: Vec<Relic>,
+    hand: Vec<(UnitKind, u8)>,
+    pattern_kind: UnitKind,
+    selected_card: Option<usize>,
+    reveal_intents: bool,
+    phasing: bool,
+    turn_captures: u32,
+    units: Vec<SavedUnit>,
+    board_items: Vec<(ItemKind, Vector2Int)>
+}
+
+impl FloorSnapshot {
+    pub fn take(world: &mut World) -> Self {
+        let mut units: Vec<SavedUnit> = world.query::<(&Unit, &Position, Option<&SpawnOrder>)>()
+            .iter(world)
+            .map(|(unit, position, order)| SavedUnit {
+                kind: unit.kind,
+                v: position.v,
+                ap: unit.ap,
+                effects: unit.effects.0.clone(),
+                spawn_order: order.map(|o| o.0)
+            })
+            .collect();
+        // query order changes after a restore, the snapshots have to compare equal anyway
+        units.sort_by_key(|u| u.spawn_order.map_or(0, |o| o + 1));
+        let mut board_items: Vec<(ItemKind, Vector2Int)> = world.query::<(&Item, &Position)>()
+            .iter(world)
+            .map(|(item, position)| (item.kind, position.v))
+            .collect();
+        board_items.sort_by_key(|(_, v)| (v.x, v.y));
+
+        let player_data = world.resource::<PlayerData>();
+        FloorSnapshot {
+            level: player_data.level,
+            coins: player_data.coins,
+            armor: player_data.armor,
+            items: player_data.items.iter().map(|i| i.kind).collect(),
+            relics: player_data.relics.clone(),
+            hand: get_saved_hand(player_data),
+            pattern_kind: player_data.pattern_kind,
+            selected_card: player_data.selected_card,
+            reveal_intents: player_data.reveal_intents,
+            phasing: player_data.phasing,
+            turn_captures: player_data.turn_captures,
+            units,
+            board_items
+        }
+    }
+    // replaces the units and the board items, the new entities are drawn by the renderers
+    pub fn restore(&self, world: &mut World) {
+        let old: Vec<Entity> = world.query_filtered::<Entity, Or<(With<Unit>, With<Item>)>>()
+            .iter(world)
+            .collect();
+        for entity in old {
+            despawn_with_children_recursive(world, entity);
+        }
+
+        for saved in self.units.iter() {
+            let mut entity = world.spawn();
+            entity.insert(Position { v: saved.v })
+                .insert(Blocker { is_targetable: true })
+                .insert(Unit {
+                    ap: saved.ap,
+                    behaviour: get_unit_behaviour(&saved.kind),
+                    kind: saved.kind,
+                    effects: StatusEffects(saved.effects.clone())
+                });
+            match saved.spawn_order {
+                Some(order) => entity.insert(NPC).insert(SpawnOrder(order)),
+                None => entity.insert(Player)
+            };
+        }
+        for (kind, v) in self.board_items.iter() {
+            world.spawn()
+                .insert(Position { v: *v })
+                .insert(Item { kind: *kind });
+        }
+
+        let mut player_data = world.resource_mut::<PlayerData>();
+        player_data.level = self.level;
+        player_data.coins = self.coins;
+        player_data.armor = self.armor;
+        player_data.items = self.items.iter().map(|kind| Item { kind: *kind }).collect();
+        player_data.relics = self.relics.clone();
+        player_data.hand = restore_hand(&self.hand);
+        player_data.reveal_intents = self.reveal_intents;
+        player_data.phasing = self.phasing;
+        player_data.turn_captures = self.turn_captures;
+        player_data.reset_behaviour();
+        match self.selected_card {
+            Some(idx) => if let Some(card) = player_data.hand.get(idx) {
+                let behaviour = card.behaviour.clone();
+                player_data.current_behaviour = behaviour;
+                player_data.pattern_kind = self.pattern_kind;
+                player_data.selected_card = Some(idx);
+            },
+            // a pattern stolen for the turn
+            None => if self.pattern_kind != UnitKind::Player {
+                player_data.current_behaviour = get_unit_behaviour(&self.pattern_kind);
+                player_data.pattern_kind = self.pattern_kind;
+            }
+        }
+    }
+}
+
+fn get_saved_hand(player_data: &PlayerData) -> Vec<(UnitKind, u8)> {
+    player_data.hand.iter().map(|c| (c.kind, c.uses)).collect()
+}
+
+fn restore_hand(hand: &[(UnitKind, u8)]) -> Vec<MoveCard> {
+    hand.iter()
+        .map(|(kind, uses)| MoveCard { kind: *kind, behaviour: get_unit_behaviour(kind), uses: *uses })
+        .collect()
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, SystemLabel)]
+pub enum RunLabel {
+    RestoreFloor
+}
+
+pub struct RunPlugin;
+
+impl Plugin for RunPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RunRng::new(get_random_seed()));
+        app.init_resource::<PendingFloor>();
+        app.add_system(restore_pending_floor.exclusive_system().at_start().label(RunLabel::RestoreFloor));
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MapGenerate)
+                .with_system(start_floor)
+        );
+        app.add_system_set(
+            SystemSet::on_enter(GameState::GameOver)
+                .with_system(remove_saved_run)
+        );
+        app.add_system(save_item_use);
+    }
+}
+
+// kept short, so it can be typed back in as a custom seed
+pub fn get_random_seed() -> u64 {
+    rand::thread_rng().gen_range(0..MAX_RANDOM_SEED)
+}
+
+// days since the epoch, the same for every player on a given day
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_daily_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86400)
+        .unwrap_or(0)
+}
+
+// there is no clock on the web build
+#[cfg(target_arch = "wasm32")]
+pub fn get_daily_seed() -> u64 {
+    get_random_seed()
+}
+
+pub fn load_saved_run() -> Option<SavedRun> {
+    storage::load(RUN_FILE)
+}
+
+pub fn new_run(seed: u64, run_rng: &mut RunRng, player_data: &mut PlayerData) {
+    *run_rng = RunRng::new(seed);
+    *player_data = PlayerData::default();
+}
+
+pub fn continue_run(saved: &SavedRun, run_rng: &mut RunRng, player_data: &mut PlayerData) {
+    *run_rng = RunRng::new(saved.seed);
+    *player_data = saved.restore();
+}
+
+// the floor about to be generated is level + 1, its start is the checkpoint
+fn start_floor(
+    mut run_rng: ResMut<RunRng>,
+    player_data: Res<PlayerData>
+) {
+    run_rng.start_floor(player_data.level);
+    storage::save(RUN_FILE, &SavedRun::new(run_rng.seed, &player_data));
+}
+
+pub fn remove_saved_run() {
+    storage::remove(RUN_FILE);
+}
+
+// the floor is saved on top of its checkpoint
+pub fn save_floor(floor: FloorSnapshot) {
+    if let Some(mut saved) = load_saved_run() {
+        saved.floor = Some(floor);
+        storage::save(RUN_FILE, &saved);
+    }
+}
+
+// written straight away, restarting the floor or quitting without saving must not forget it
+fn save_item_use(
+    mut ev_game: EventReader<GameEvent>
+) {
+    if !ev_game.iter().any(|ev| matches!(ev, GameEvent::ItemUsed { .. })) { return; }
+    if let Some(mut saved) = load_saved_run() {
+        if saved.used_item { return; }
+        saved.used_item = true;
+        storage::save(RUN_FILE, &saved);
+    }
+}
+
+fn restore_pending_floor(world: &mut World) {
+    if world.resource::<State<GameState>>().current() != &GameState::PlayerTurn { return; }
+    let floor = match world.resource_mut::<PendingFloor>().0.take() {
+        Some(f) => f,
+        None => return
+    };
+    // the floor was generated from the same seed, so only the level has to match
+    if floor.level != world.resource::<PlayerData>().level { return; }
+    floor.restore(world);
+    world.resource_mut::<Events<RedrawUIEvent>>().send(RedrawUIEvent);
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::palette::{Palette, PaletteKind, get_palette};
+use crate::storage;
+use crate::units::player::PatternMode;
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window_size: (f32, f32),
+    pub fullscreen: bool,
+    // multiplies every animation rate
+    pub animation_speed: f32,
+    // extra multiplier while the fast-forward key is held
+    pub fast_forward_speed: f32,
+    // npcs skip the move animation
+    pub instant_npc_moves: bool,
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub palette: PaletteKind,
+    pub pattern_mode: PatternMode,
+    pub language: Language
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            window_size: (800., 800.),
+            fullscreen: false,
+            animation_speed: 1.,
+            fast_forward_speed: 4.,
+            instant_npc_moves: false,
+            master_volume: 1.,
+            music_volume: 0.7,
+            sfx_volume: 1.,
+            palette: PaletteKind::Default,
+            pattern_mode: PatternMode::Hand,
+            language: Language::English
+        }
+    }
+}
+
+pub fn get_window_sizes() -> Vec<(f32, f32)> {
+    vec![(600., 600.), (800., 800.), (1000., 1000.), (1200., 1200.)]
+}
+
+pub fn get_pattern_modes() -> Vec<PatternMode> {
+    vec![PatternMode::Hand, PatternMode::Steal]
+}
+
+pub fn get_pattern_mode_name(mode: PatternMode) -> &'static str {
+    match mode {
+        PatternMode::Hand => "Move cards",
+        PatternMode::Steal => "Steal for a turn"
+    }
+}
+
+pub fn get_animation_speeds() -> Vec<f32> {
+    vec![0.5, 1., 1.5, 2., 3.]
+}
+
+pub fn get_fast_forward_speeds() -> Vec<f32> {
+    vec![2., 4., 8.]
+}
+
+// ui text is not translated yet, so english is the only choice
+pub fn get_languages() -> Vec<Language> {
+    vec![Language::English]
+}
+
+pub fn get_language_name(language: Language) -> &'static str {
+    match language {
+        Language::English => "English"
+    }
+}
+
+pub fn load_settings() -> Settings {
+    storage::load(SETTINGS_FILE).unwrap_or_default()
+}
+
+// the window plugin reads the descriptor once, so it has to be inserted before DefaultPlugins
+pub fn get_window_descriptor(settings: &Settings) -> WindowDescriptor {
+    WindowDescriptor {
+        width: settings.window_size.0,
+        height: settings.window_size.1,
+        mode: get_window_mode(settings.fullscreen),
+        ..Default::default()
+    }
+}
+
+fn get_window_mode(fullscreen: bool) -> WindowMode {
+    match fullscreen {
+        true => WindowMode::BorderlessFullscreen,
+        false => WindowMode::Windowed
+    }
+}
+
+// expects the Settings resource to be inserted by main, together with the window descriptor
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let palette = get_palette(app.world.resource::<Settings>().palette);
+        app.insert_resource(palette);
+        app.add_system(apply_settings);
+    }
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut palette: ResMut<Palette>,
+    mut windows: ResMut<Windows>
+) {
+    if !settings.is_changed() || settings.is_added() { return; }
+    if palette.kind != settings.palette {
+        *palette = get_palette(settings.palette);
+    }
+    if let Some(window) = windows.get_primary_mut() {
+        let mode = get_window_mode(settings.fullscreen);
+        if window.mode() != mode { window.set_mode(mode); }
+        let (width, height) = settings.window_size;
+        if window.requested_width() != width || window.requested_height() != height {
+            window.set_resolution(width, height);
+        }
+    }
+    storage::save(SETTINGS_FILE, &*settings);
+}
@@ -0,0 +1,155 @@
+use crate::events::GameEvent;
+use crate::states::GameState;
+use crate::units::behaviour::MoveKind;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Step,
+    Jump,
+    Capture,
+    ArmorBlock,
+    Bush,
+    ItemPick,
+    ItemUse,
+    LevelTransition
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Music {
+    Menu,
+    Game,
+    GameOver
+}
+
+pub fn get_sfx_kinds() -> Vec<Sfx> {
+    vec![
+        Sfx::Step, Sfx::Jump, Sfx::Capture, Sfx::ArmorBlock,
+        Sfx::Bush, Sfx::ItemPick, Sfx::ItemUse, Sfx::LevelTransition
+    ]
+}
+
+pub fn get_music_kinds() -> Vec<Music> {
+    vec![Music::Menu, Music::Game, Music::GameOver]
+}
+
+pub fn get_sfx_path(sfx: Sfx) -> &'static str {
+    match sfx {
+        Sfx::Step => "sfx/step.ogg",
+        Sfx::Jump => "sfx/jump.ogg",
+        Sfx::Capture => "sfx/capture.ogg",
+        Sfx::ArmorBlock => "sfx/armor_block.ogg",
+        Sfx::Bush => "sfx/bush.ogg",
+        Sfx::ItemPick => "sfx/item_pick.ogg",
+        Sfx::ItemUse => "sfx/item_use.ogg",
+        Sfx::LevelTransition => "sfx/level_transition.ogg"
+    }
+}
+
+pub fn get_music_path(music: Music) -> &'static str {
+    match music {
+        Music::Menu => "music/menu.ogg",
+        Music::Game => "music/game.ogg",
+        Music::GameOver => "music/game_over.ogg"
+    }
+}
+
+pub fn get_event_sfx(ev: &GameEvent) -> Option<Sfx> {
+    match *ev {
+        GameEvent::UnitMoved { move_kind: MoveKind::Step, .. } => Some(Sfx::Step),
+        GameEvent::UnitMoved { move_kind: MoveKind::Jump, .. } => Some(Sfx::Jump),
+        GameEvent::UnitCaptured { .. } => Some(Sfx::Capture),
+        GameEvent::ArmorBlocked { .. } => Some(Sfx::ArmorBlock),
+        GameEvent::BushEntered { .. } => Some(Sfx::Bush),
+        GameEvent::ItemPicked { .. } => Some(Sfx::ItemPick),
+        GameEvent::ItemUsed { .. } => Some(Sfx::ItemUse),
+        GameEvent::LevelCleared { .. } => Some(Sfx::LevelTransition),
+        _ => None
+    }
+}
+
+// None keeps the current track - pause and settings are opened over both the menu and the game
+pub fn get_state_music(state: &GameState) -> Option<Music> {
+    match state {
+        GameState::MainMenu | GameState::Achievements | GameState::Codex | GameState::Statistics => Some(Music::Menu),
+        GameState::MapGenerate | GameState::Spawning | GameState::PlayerTurn
+            | GameState::NPCTurn | GameState::Interlude => Some(Music::Game),
+        GameState::GameOver => Some(Music::GameOver),
+        GameState::LoadAssets | GameState::Settings | GameState::Paused => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Entity;
+
+    use crate::items::ItemKind;
+    use crate::units::UnitKind;
+    use crate::vectors::Vector2Int;
+    use super::*;
+
+    fn moved(move_kind: MoveKind) -> GameEvent {
+        GameEvent::UnitMoved {
+            entity: Entity::from_raw(0),
+            move_kind,
+            from: Vector2Int::new(0, 0),
+            to: Vector2Int::new(0, 1)
+        }
+    }
+
+    #[test]
+    fn moves_sound_by_move_kind() {
+        assert_eq!(get_event_sfx(&moved(MoveKind::Step)), Some(Sfx::Step));
+        assert_eq!(get_event_sfx(&moved(MoveKind::Jump)), Some(Sfx::Jump));
+    }
+
+    #[test]
+    fn game_events_map_to_sfx() {
+        let attacker = Entity::from_raw(1);
+        let cases = [
+            (GameEvent::UnitCaptured {
+                attacker,
+                attacker_kind: UnitKind::Player,
+                victim: Entity::from_raw(2),
+                victim_kind: UnitKind::Fox
+            }, Some(Sfx::Capture)),
+            (GameEvent::ArmorBlocked { attacker, attacker_kind: UnitKind::Fox }, Some(Sfx::ArmorBlock)),
+            (GameEvent::BushEntered { kind: UnitKind::Player, paused: true }, Some(Sfx::Bush)),
+            (GameEvent::ItemPicked { kind: ItemKind::Bomb }, Some(Sfx::ItemPick)),
+            (GameEvent::ItemUsed { kind: ItemKind::Bomb }, Some(Sfx::ItemUse)),
+            (GameEvent::LevelCleared { level: 1 }, Some(Sfx::LevelTransition)),
+            // covered by the capture sound
+            (GameEvent::UnitKilled { entity: attacker, kind: UnitKind::Fox, v: Vector2Int::new(0, 0) }, None),
+            (GameEvent::LevelStarted { level: 1 }, None),
+            (GameEvent::PlayerDied { level: 1 }, None)
+        ];
+        for (ev, sfx) in cases {
+            assert_eq!(get_event_sfx(&ev), sfx, "{:?}", ev);
+        }
+    }
+
+    #[test]
+    fn states_map_to_music() {
+        assert_eq!(get_state_music(&GameState::MainMenu), Some(Music::Menu));
+        assert_eq!(get_state_music(&GameState::Codex), Some(Music::Menu));
+        assert_eq!(get_state_music(&GameState::PlayerTurn), Some(Music::Game));
+        assert_eq!(get_state_music(&GameState::NPCTurn), Some(Music::Game));
+        assert_eq!(get_state_music(&GameState::Interlude), Some(Music::Game));
+        assert_eq!(get_state_music(&GameState::GameOver), Some(Music::GameOver));
+    }
+
+    #[test]
+    fn every_sound_has_a_file() {
+        let paths = get_sfx_kinds().into_iter().map(get_sfx_path)
+            .chain(get_music_kinds().into_iter().map(get_music_path));
+        for path in paths {
+            assert!(std::path::Path::new("assets").join(path).is_file(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn overlay_states_keep_the_music() {
+        assert_eq!(get_state_music(&GameState::Paused), None);
+        assert_eq!(get_state_music(&GameState::Settings), None);
+        assert_eq!(get_state_music(&GameState::LoadAssets), None);
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use bevy::asset::LoadState;
+use bevy::audio::AudioSink;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::events::GameEvent;
+use crate::settings::Settings;
+use crate::states::GameState;
+
+pub mod data;
+
+use data::{Music, Sfx};
+
+// other systems can request a sound directly, game events are translated in queue_event_sfx
+pub struct SfxEvent(pub Sfx);
+
+// not part of the AssetList - sound files are optional, the missing ones are never loaded
+#[derive(Default)]
+pub struct SoundAssets {
+    sfx: HashMap<Sfx, Handle<AudioSource>>,
+    music: HashMap<Music, Handle<AudioSource>>
+}
+
+#[derive(Default)]
+pub struct MusicPlayer {
+    current: Option<Music>,
+    sink: Option<Handle<AudioSink>>
+}
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SfxEvent>();
+        app.init_resource::<SoundAssets>();
+        app.init_resource::<MusicPlayer>();
+        app.add_system_set(
+            SystemSet::on_enter(GameState::LoadAssets)
+                .with_system(load_assets)
+        );
+        app.add_system(queue_event_sfx);
+        app.add_system(play_sfx.after(queue_event_sfx));
+        app.add_system(play_music);
+    }
+}
+
+fn load_assets(
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<SoundAssets>
+) {
+    // the web build can't check for files, so it stays silent
+    let exists = |path: &str| asset_server.asset_io().is_file(Path::new(path));
+    for sfx in data::get_sfx_kinds() {
+        let path = data::get_sfx_path(sfx);
+        if exists(path) { assets.sfx.insert(sfx, asset_server.load(path)); }
+    }
+    for music in data::get_music_kinds() {
+        let path = data::get_music_path(music);
+        if exists(path) { assets.music.insert(music, asset_server.load(path)); }
+    }
+}
+
+fn queue_event_sfx(
+    mut ev_game: EventReader<GameEvent>,
+    mut ev_sfx: EventWriter<SfxEvent>
+) {
+    for ev in ev_game.iter() {
+        if let Some(sfx) = data::get_event_sfx(ev) {
+            ev_sfx.send(SfxEvent(sfx));
+        }
+    }
+}
+
+fn play_sfx(
+    mut ev_sfx: EventReader<SfxEvent>,
+    audio: Res<Audio>,
+    assets: Res<SoundAssets>,
+    sources: Res<Assets<AudioSource>>,
+    settings: Res<Settings>
+) {
+    let volume = settings.master_volume * settings.sfx_volume;
+    let queued = dedupe_sfx(ev_sfx.iter().map(|ev| ev.0));
+    if volume <= 0. { return; }
+    for sfx in queued {
+        let handle = match assets.sfx.get(&sfx) {
+            Some(h) => h,
+            None => continue
+        };
+        // unloaded sounds would wait in the audio queue forever
+        if sources.get(handle).is_none() { continue; }
+        audio.play_with_settings(handle.clone(), PlaybackSettings::ONCE.with_volume(volume));
+    }
+}
+
+// several units can make the same sound in one frame, it is played once
+fn dedupe_sfx(sfx: impl Iterator<Item=Sfx>) -> Vec<Sfx> {
+    let mut played = HashSet::new();
+    sfx.filter(|s| played.insert(*s)).collect()
+}
+
+fn play_music(
+    game_state: Res<State<GameState>>,
+    audio: Res<Audio>,
+    assets: Res<SoundAssets>,
+    asset_server: Res<AssetServer>,
+    sources: Res<Assets<AudioSource>>,
+    sinks: Res<Assets<AudioSink>>,
+    settings: Res<Settings>,
+    mut player: ResMut<MusicPlayer>
+) {
+    let volume = settings.master_volume * settings.music_volume;
+    if settings.is_changed() {
+        if let Some(sink) = player.sink.as_ref().and_then(|h| sinks.get(h)) {
+            sink.set_volume(volume);
+        }
+    }
+
+    let music = match data::get_state_music(game_state.current()) {
+        Some(m) => m,
+        None => return
+    };
+    if player.current == Some(music) { return; }
+    // a missing track still stops the previous one
+    let handle = assets.music.get(&music);
+    if let Some(handle) = handle {
+        // wait for the track to load, a failed one is skipped
+        if asset_server.get_load_state(handle) == LoadState::Loading { return; }
+    }
+
+    if let Some(sink) = player.sink.take().as_ref().and_then(|h| sinks.get(h)) {
+        sink.stop();
+    }
+    player.current = Some(music);
+    if let Some(handle) = handle.filter(|h| sources.get(*h).is_some()) {
+        let sink = audio.play_with_settings(handle.clone(), PlaybackSettings::LOOP.with_volume(volume));
+        player.sink = Some(sinks.get_handle(sink));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedupe_keeps_the_first_of_each_sound() {
+        let sfx = vec![Sfx::Step, Sfx::Capture, Sfx::Step, Sfx::Jump, Sfx::Capture];
+        assert_eq!(dedupe_sfx(sfx.into_iter()), vec![Sfx::Step, Sfx::Capture, Sfx::Jump]);
+    }
+
+    #[test]
+    fn dedupe_of_nothing_is_empty() {
+        assert!(dedupe_sfx(std::iter::empty()).is_empty());
+    }
+}
@@ -4,11 +4,22 @@ use bevy::ecs::schedule::SystemLabel;
 pub enum GameState {
     LoadAssets,
     MainMenu,
+    // unlocked achievements list, reached from the main menu
+    Achievements,
+    // every unit, item and tile met so far
+    Codex,
+    Settings,
+    // totals over every run
+    Statistics,
     MapGenerate,
     Spawning,
     PlayerTurn,
     NPCTurn,
-    GameOver
+    // rewards and shopping between levels
+    Interlude,
+    GameOver,
+    // pushed over the turn states, which stay frozen underneath
+    Paused
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::events::GameEvent;
+use crate::profile::Profile;
+use crate::units::UnitKind;
+
+// totals over every run, kept in the profile
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Statistics {
+    pub runs: u32,
+    pub deepest_level: u32,
+    pub floors_cleared: u32,
+    pub captures: u32,
+    pub items_used: u32,
+    pub deaths: u32
+}
+
+pub fn get_statistic_rows(stats: &Statistics) -> Vec<(&'static str, u32)> {
+    vec![
+        ("Runs", stats.runs),
+        ("Deepest level", stats.deepest_level),
+        ("Floors cleared", stats.floors_cleared),
+        ("Captures", stats.captures),
+        ("Items used", stats.items_used),
+        ("Deaths", stats.deaths)
+    ]
+}
+
+pub struct StatisticsPlugin;
+
+impl Plugin for StatisticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(track_statistics);
+    }
+}
+
+fn track_statistics(
+    mut ev_game: EventReader<GameEvent>,
+    mut profile: ResMut<Profile>
+) {
+    for ev in ev_game.iter() {
+        // the profile is only touched by counted events, every change gets saved
+        match *ev {
+            GameEvent::LevelStarted { level } => {
+                let stats = &mut profile.stats;
+                if level == 1 { stats.runs += 1; }
+                stats.deepest_level = stats.deepest_level.max(level);
+            },
+            GameEvent::LevelCleared { .. } => profile.stats.floors_cleared += 1,
+            GameEvent::UnitCaptured { attacker_kind: UnitKind::Player, .. } => profile.stats.captures += 1,
+            GameEvent::ItemUsed { .. } => profile.stats.items_used += 1,
+            GameEvent::PlayerDied { .. } => profile.stats.deaths += 1,
+            _ => ()
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::log::{error, warn};
+#[cfg(not(target_arch = "wasm32"))]
+use std::fs;
+
+// local ron files, kept next to the game - there is no file system on the web build
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let data = fs::read_to_string(name).ok()?;
+    match ron::from_str(&data) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            warn!("could not parse {}: {}", name, e);
+            None
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let data = match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(d) => d,
+        Err(e) => {
+            error!("could not serialize {}: {}", name, e);
+            return;
+        }
+    };
+    if let Err(e) = fs::write(name, data) {
+        error!("could not save {}: {}", name, e);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn remove(name: &str) {
+    let _ = fs::remove_file(name);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load<T: DeserializeOwned>(_name: &str) -> Option<T> {
+    None
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save<T: Serialize>(_name: &str, _value: &T) {}
+
+#[cfg(target_arch = "wasm32")]
+pub fn remove(_name: &str) {}
@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::achievements::{
+    AchievementUnlockedEvent,
+    get_achievements,
+    get_achievement_description,
+    get_achievement_name
+};
+use crate::graphics::palette::Palette;
+use crate::profile::Profile;
+
+use super::player_menu::TRANSPARENT;
+
+const BACKGROUND_COLOR: Color = Color::Rgba { red: 0.11, green: 0.09, blue: 0.08, alpha: 1. };
+const LOCKED_COLOR: Color = Color::Rgba { red: 0.4, green: 0.4, blue: 0.4, alpha: 1. };
+const TOAST_TIME: f32 = 3.;
+const TOAST_HEIGHT: f32 = 40.;
+const TOAST_TOP: f32 = 16.;
+
+#[derive(Component)]
+pub struct AchievementList;
+
+#[derive(Component)]
+pub struct Toast {
+    timer: Timer
+}
+
+pub fn clear_achievements(
+    mut commands: Commands,
+    query: Query<Entity, With<AchievementList>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity)
+            .despawn_recursive()
+    }
+}
+
+pub fn draw_achievements(
+    mut commands: Commands,
+    profile: Res<Profile>,
+    font_assets: Res<super::FontAssets>,
+    palette: Res<Palette>
+) {
+    let text_style = TextStyle {
+        color: palette.status,
+        font: font_assets.font.clone(),
+        font_size: 32.,
+    };
+
+    commands.spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: BACKGROUND_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(AchievementList)
+        .with_children(|parent| {
+            let unlocked = profile.achievements.len();
+            parent.spawn_bundle(TextBundle::from_section(
+                format!("Achievements {}/{}", unlocked, get_achievements().len()),
+                text_style.clone()
+            ));
+            for achievement in get_achievements() {
+                let color = match profile.achievements.contains(&achievement) {
+                    true => palette.status,
+                    false => LOCKED_COLOR
+                };
+                parent.spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::ColumnReverse,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::all(Val::Px(8.)),
+                        ..Default::default()
+                    },
+                    color: TRANSPARENT.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle::from_section(
+                        get_achievement_name(achievement),
+                        TextStyle { color, font_size: 24., ..text_style.clone() }
+                    ));
+                    parent.spawn_bundle(TextBundle::from_section(
+                        get_achievement_description(achievement),
+                        TextStyle { color, font_size: 16., ..text_style.clone() }
+                    ));
+                });
+            }
+            parent.spawn_bundle(TextBundle::from_section(
+                "Click to go back",
+                TextStyle { font_size: 16., ..text_style.clone() }
+            ));
+        });
+}
+
+pub fn show_toasts(
+    mut commands: Commands,
+    mut ev_unlocked: EventReader<AchievementUnlockedEvent>,
+    toast_query: Query<&Toast>,
+    font_assets: Option<Res<super::FontAssets>>,
+    palette: Res<Palette>
+) {
+    let font_assets = match font_assets {
+        Some(f) => f,
+        None => return
+    };
+    // new toasts are stacked below the ones still shown
+    let shown = toast_query.iter().len();
+    for (count, ev) in (shown..).zip(ev_unlocked.iter()) {
+        commands.spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(TOAST_TOP + count as f32 * TOAST_HEIGHT),
+                        left: Val::Px(TOAST_TOP),
+                        ..Default::default()
+                    },
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..Default::default()
+                },
+                color: BACKGROUND_COLOR.into(),
+                ..Default::default()
+            })
+            .insert(Toast { timer: Timer::from_seconds(TOAST_TIME, false) })
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle::from_section(
+                    format!("Achievement unlocked: {}", get_achievement_name(ev.0)),
+                    TextStyle {
+                        color: palette.status,
+                        font: font_assets.font.clone(),
+                        font_size: 16.
+                    }
+                ));
+            });
+    }
+}
+
+pub fn update_toasts(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Toast)>,
+    time: Res<Time>
+) {
+    for (entity, mut toast) in query.iter_mut() {
+        toast.timer.tick(time.delta());
+        if toast.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
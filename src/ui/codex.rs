@@ -0,0 +1,316 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::actions::{Action, Actions};
+use crate::board::tile::{get_tile_description, get_tile_types};
+use crate::graphics::{
+    palette::Palette,
+    unit_renderer::{UnitSprites, get_unit_image}
+};
+use crate::items::data::{get_item_description, get_item_types};
+use crate::profile::Profile;
+use crate::states::GameState;
+use crate::units::{
+    UnitKind,
+    data::{get_npc_types, get_unit_behaviour, get_unit_rank, get_unit_rules, get_unit_speed}
+};
+
+use super::player_menu::{PlayerMenuAssets, TRANSPARENT};
+
+const BACKGROUND_COLOR: Color = Color::Rgba { red: 0.11, green: 0.09, blue: 0.08, alpha: 1. };
+const LOCKED_COLOR: Color = Color::Rgba { red: 0.4, green: 0.4, blue: 0.4, alpha: 1. };
+const CARD_WIDTH: f32 = 170.;
+const CARD_HEIGHT: f32 = 240.;
+const CARD_MARGIN: f32 = 8.;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum CodexPage {
+    #[default]
+    Units,
+    Items,
+    Tiles
+}
+
+const PAGES: [CodexPage; 3] = [CodexPage::Units, CodexPage::Items, CodexPage::Tiles];
+
+#[derive(Component)]
+pub struct CodexMenu;
+
+#[derive(Component)]
+pub struct CodexTab(CodexPage);
+
+pub fn reset_codex(
+    mut page: ResMut<CodexPage>
+) {
+    *page = CodexPage::Units;
+}
+
+pub fn clear_codex(
+    mut commands: Commands,
+    query: Query<Entity, With<CodexMenu>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity)
+            .despawn_recursive()
+    }
+}
+
+pub fn codex_input(
+    mut actions: ResMut<Actions>,
+    mut game_state: ResMut<State<GameState>>,
+    mut page: ResMut<CodexPage>,
+    interactions: Query<(&Interaction, &CodexTab), Changed<Interaction>>
+) {
+    for (interaction, tab) in interactions.iter() {
+        if *interaction == Interaction::Clicked && *page != tab.0 { *page = tab.0; }
+    }
+
+    let idx = PAGES.iter().position(|p| *p == *page).unwrap_or(0);
+    if actions.just_pressed(Action::Right) {
+        *page = PAGES[(idx + 1) % PAGES.len()];
+    }
+    if actions.just_pressed(Action::Left) {
+        *page = PAGES[(idx + PAGES.len() - 1) % PAGES.len()];
+    }
+    if actions.just_pressed(Action::Back) {
+        actions.clear();
+        let _ = game_state.set(GameState::MainMenu);
+    }
+}
+
+pub fn draw_codex(
+    mut commands: Commands,
+    page: Res<CodexPage>,
+    query: Query<Entity, With<CodexMenu>>,
+    profile: Res<Profile>,
+    font_assets: Res<super::FontAssets>,
+    menu_assets: Res<PlayerMenuAssets>,
+    palette: Res<Palette>,
+    sprites: Res<UnitSprites>,
+    atlases: Res<Assets<TextureAtlas>>,
+    mut images: ResMut<Assets<Image>>,
+    mut unit_images: Local<HashMap<UnitKind, Handle<Image>>>
+) {
+    if !page.is_changed() { return; }
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let unit_kinds: Vec<UnitKind> = [UnitKind::Player].into_iter()
+        .chain(get_npc_types())
+        .collect();
+    if unit_images.is_empty() {
+        for kind in unit_kinds.iter() {
+            if let Some(handle) = get_unit_image(kind, &mut images, &atlases, &sprites) {
+                unit_images.insert(*kind, handle);
+            }
+        }
+    }
+
+    let text_style = TextStyle {
+        color: palette.status,
+        font: font_assets.font.clone(),
+        font_size: 32.,
+    };
+    let tiles: Vec<_> = get_tile_types().into_iter()
+        .filter_map(|k| get_tile_description(&k).map(|d| (k, d)))
+        .collect();
+    let tabs = [
+        (CodexPage::Units, "Units", profile.seen_units.len(), unit_kinds.len()),
+        (CodexPage::Items, "Items", profile.seen_items.len(), get_item_types().len()),
+        (CodexPage::Tiles, "Tiles", tiles.iter().filter(|(k, _)| profile.seen_tiles.contains(k)).count(), tiles.len())
+    ];
+
+    commands.spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: BACKGROUND_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(CodexMenu)
+        .with_children(|parent| {
+            parent.spawn_bundle(NodeBundle {
+                style: Style {
+                    margin: UiRect::all(Val::Px(CARD_MARGIN)),
+                    ..Default::default()
+                },
+                color: TRANSPARENT.into(),
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                for (tab, label, seen, total) in tabs {
+                    let color = match tab == *page {
+                        true => palette.button_click,
+                        false => palette.button
+                    };
+                    parent.spawn_bundle(ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(8.)),
+                            margin: UiRect::all(Val::Px(4.)),
+                            ..Default::default()
+                        },
+                        color: color.into(),
+                        image: UiImage(menu_assets.button_image.clone()),
+                        ..Default::default()
+                    })
+                    .insert(CodexTab(tab))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle::from_section(
+                            format!("{} {}/{}", label, seen, total),
+                            TextStyle { color: palette.button_text, font_size: 24., ..text_style.clone() }
+                        ));
+                    });
+                }
+            });
+
+            match *page {
+                CodexPage::Units => {
+                    parent.spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(100.), Val::Undefined),
+                            flex_wrap: FlexWrap::WrapReverse,
+                            justify_content: JustifyContent::Center,
+                            ..Default::default()
+                        },
+                        color: TRANSPARENT.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for kind in unit_kinds.iter() {
+                            let image = match profile.seen_units.contains(kind) {
+                                true => unit_images.get(kind).cloned(),
+                                false => None
+                            };
+                            spawn_unit_card(parent, kind, image, &text_style, &palette);
+                        }
+                    });
+                },
+                CodexPage::Items => {
+                    for kind in get_item_types() {
+                        let seen = profile.seen_items.contains(&kind);
+                        let (label, color) = match seen {
+                            true => (format!("{:?}: {}", kind, get_item_description(kind)), palette.status),
+                            false => ("???".to_string(), LOCKED_COLOR)
+                        };
+                        parent.spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(4. * CARD_WIDTH), Val::Undefined),
+                                align_items: AlignItems::Center,
+                                margin: UiRect::all(Val::Px(2.)),
+                                ..Default::default()
+                            },
+                            color: TRANSPARENT.into(),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn_bundle(ImageBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(32.), Val::Px(32.)),
+                                    margin: UiRect::new(Val::Px(0.), Val::Px(8.), Val::Px(0.), Val::Px(0.)),
+                                    ..Default::default()
+                                },
+                                color: match seen {
+                                    true => palette.status.into(),
+                                    false => TRANSPARENT.into()
+                                },
+                                image: UiImage(menu_assets.item_images[&kind].clone()),
+                                ..Default::default()
+                            });
+                            parent.spawn_bundle(TextBundle::from_section(
+                                label,
+                                TextStyle { color, font_size: 16., ..text_style.clone() }
+                            ));
+                        });
+                    }
+                },
+                CodexPage::Tiles => {
+                    for (kind, description) in tiles.iter() {
+                        let (label, color) = match profile.seen_tiles.contains(kind) {
+                            true => (*description, palette.status),
+                            false => ("???", LOCKED_COLOR)
+                        };
+                        parent.spawn_bundle(TextBundle::from_section(
+                            label,
+                            TextStyle { color, font_size: 24., ..text_style.clone() }
+                        )
+                        .with_style(Style {
+                            margin: UiRect::all(Val::Px(CARD_MARGIN)),
+                            ..Default::default()
+                        }));
+                    }
+                }
+            }
+
+            parent.spawn_bundle(TextBundle::from_section(
+                "Arrows to switch pages, Esc to go back",
+                TextStyle { font_size: 16., ..text_style.clone() }
+            )
+            .with_style(Style {
+                margin: UiRect::all(Val::Px(CARD_MARGIN)),
+                ..Default::default()
+            }));
+        });
+}
+
+// locked units come without an image
+fn spawn_unit_card(
+    parent: &mut ChildBuilder,
+    kind: &UnitKind,
+    image: Option<Handle<Image>>,
+    text_style: &TextStyle,
+    palette: &Palette
+) {
+    parent.spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(CARD_WIDTH), Val::Px(CARD_HEIGHT)),
+                margin: UiRect::all(Val::Px(CARD_MARGIN)),
+                padding: UiRect::all(Val::Px(CARD_MARGIN)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: palette.button.into(),
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            let image = match image {
+                Some(i) => i,
+                None => {
+                    parent.spawn_bundle(TextBundle::from_section(
+                        "???",
+                        TextStyle { color: LOCKED_COLOR, font_size: 24., ..text_style.clone() }
+                    ));
+                    return;
+                }
+            };
+            parent.spawn_bundle(ImageBundle {
+                style: Style {
+                    size: Size::new(Val::Px(48.), Val::Px(48.)),
+                    ..Default::default()
+                },
+                image: UiImage(image),
+                ..Default::default()
+            });
+            let lines = [
+                (format!("{:?}", kind), 24.),
+                (format!("Rank {}  Speed {}", get_unit_rank(kind), get_unit_speed(kind)), 16.),
+                (get_unit_rules(kind).to_string(), 12.)
+            ];
+            for (line, font_size) in lines {
+                parent.spawn_bundle(TextBundle::from_section(
+                    line,
+                    TextStyle { color: palette.button_text, font_size, ..text_style.clone() }
+                )
+                .with_style(Style {
+                    max_size: Size::new(Val::Px(CARD_WIDTH - 2. * CARD_MARGIN), Val::Undefined),
+                    ..Default::default()
+                }));
+            }
+            super::tooltip::spawn_diagram(parent, &get_unit_behaviour(kind).pattern, palette);
+        });
+}
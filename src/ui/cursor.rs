@@ -2,18 +2,36 @@ use bevy::prelude::*;
 use bevy::{render, sprite};
 
 use crate::board::{Blocker, Board, Position};
-use crate::graphics::{TILE_SIZE, CURSOR_Z};
+use crate::graphics::{TILE_SIZE, CURSOR_Z, palette::Palette};
+use crate::items::{ItemTargeting, get_legal_targets};
 use crate::units::{
     Unit,
+    UnitKind,
+    npc::NPC,
     player::{Player, PlayerData}
 };
 use crate::vectors::Vector2Int;
 
+// danger square opacity by the number of attackers
+const DANGER_ALPHA: [f32; 3] = [0.4, 0.65, 0.9];
+
 #[derive(Component)]
 pub struct Cursor;
 
 pub struct CursorAssets {
     material: Handle<ColorMaterial>,
+    // squares with a unit to capture
+    capture_material: Handle<ColorMaterial>,
+    npc_material: Handle<ColorMaterial>,
+    target_material: Handle<ColorMaterial>,
+    // by the number of attackers, the last one is used for anything above
+    danger_materials: Vec<Handle<ColorMaterial>>
+}
+
+// every square threatened by npcs is shown when visible
+#[derive(Default)]
+pub struct DangerMap {
+    pub visible: bool
 }
 
 pub fn draw_cursor(
@@ -28,6 +46,9 @@ pub fn draw_cursor(
     player_query: Query<Entity, With<Player>>,
     board_query: Query<&Board>,
     player_data: Res<PlayerData>,
+    targeting: Res<ItemTargeting>,
+    npc_query: Query<(Entity, &Position), With<NPC>>,
+    danger_map: Res<DangerMap>,
 ) {
     for _ in ev_draw_cursor.iter() {
         destroy_cursor(&mut commands, &cursor_query);
@@ -47,43 +68,122 @@ pub fn draw_cursor(
             }
         };
 
-        let behaviour = match input_assets.selected_npc {
-            Some(e) => {
-                if let Ok((u, _, _)) = unit_query.get(e) { &u.behaviour } else { return; }
-            },
-            None => {
-                &player_data.current_behaviour
-            }
-        };
-
         let board = board_query.get_single().unwrap();
 
-        let (_, position, _) = unit_query.get(entity).unwrap();
+        let (unit, position, _) = unit_query.get(entity).unwrap();
     
         let mut blockers:  Vec<(&Position, &Blocker)> = blocker_query.iter().collect();
         let unit_blockers: Vec<(&Position, &Blocker)> = unit_query.iter().map(|(_, p, b)| (p, b)).collect();
         blockers.extend(unit_blockers);
     
-        let range = behaviour.possible_positions(
-            position.v, 
-            board,
-            &blockers
-        );
-    
-        let mesh = create_cursor_mesh(&range);
+        if let Some(idx) = targeting.item {
+            if let Some(item) = player_data.items.get(idx) {
+                let npcs: Vec<(Entity, Vector2Int)> = npc_query.iter()
+                    .map(|(e, p)| (e, p.v))
+                    .collect();
+                let blocker_positions: Vec<Vector2Int> = blockers.iter()
+                    .map(|(p, _)| p.v)
+                    .collect();
+                let targets = get_legal_targets(item.kind, &npcs, &blocker_positions, board);
+                spawn_cursor(&mut commands, &mut meshes, &targets, assets.target_material.clone(), CURSOR_Z - 1.);
+                if let Some(v) = targeting.target {
+                    spawn_cursor(&mut commands, &mut meshes, &vec![v], assets.material.clone(), CURSOR_Z);
+                }
+            }
+            return;
+        }
+
+        let range = match input_assets.selected_npc {
+            Some(e) => {
+                if let Ok((u, _, _)) = unit_query.get(e) {
+                    u.behaviour.possible_positions(position.v, board, &blockers)
+                } else { return; }
+            },
+            None => player_data.possible_positions(position.v, board, &blockers)
+        };
+        let (captures, moves): (Vec<Vector2Int>, Vec<Vector2Int>) = unit.effects.restrict_moves(range, &blockers)
+            .into_iter()
+            .partition(|v| unit_query.iter().any(|(_, p, _)| p.v == *v));
     
-        commands.spawn_bundle(sprite::MaterialMesh2dBundle {
-            mesh: sprite::Mesh2dHandle(meshes.add(mesh)),
-            material: assets.material.clone(),
-            transform: Transform::default()
-                .with_scale(Vec3::new(TILE_SIZE, TILE_SIZE, 0.0))
-                .with_translation(Vec3::new(0., 0., CURSOR_Z)),
-            ..Default::default()
-        })
-        .insert(Cursor);
+        // an inspected npc's range is dotted, so it isn't mistaken for the player's
+        let move_material = match input_assets.selected_npc {
+            Some(_) => assets.npc_material.clone(),
+            None => assets.material.clone()
+        };
+        spawn_cursor(&mut commands, &mut meshes, &moves, move_material, CURSOR_Z);
+        spawn_cursor(&mut commands, &mut meshes, &captures, assets.capture_material.clone(), CURSOR_Z);
+
+        if player_data.reveal_intents && input_assets.selected_npc.is_none() {
+            let mut npc_range = Vec::new();
+            for (unit, npc_position, _) in unit_query.iter() {
+                if let UnitKind::Player = unit.kind { continue; }
+                npc_range.extend(unit.effects.restrict_moves(
+                    unit.behaviour.possible_positions(npc_position.v, board, &blockers),
+                    &blockers
+                ));
+            }
+            spawn_cursor(&mut commands, &mut meshes, &npc_range, assets.npc_material.clone(), CURSOR_Z - 1.);
+        }
+
+        if danger_map.visible && input_assets.selected_npc.is_none() {
+            let threats = get_threat_counts(&unit_query, board, &blockers);
+            for (idx, material) in assets.danger_materials.iter().enumerate() {
+                let last = idx == assets.danger_materials.len() - 1;
+                let squares: Vec<Vector2Int> = threats.iter()
+                    .filter(|(_, count)| *count == idx + 1 || (last && *count > idx))
+                    .map(|(v, _)| *v)
+                    .collect();
+                spawn_cursor(&mut commands, &mut meshes, &squares, material.clone(), CURSOR_Z - 2.);
+            }
+        }
     }
 }
 
+// squares any npc could capture in its next move, with the number of attackers
+fn get_threat_counts(
+    unit_query: &Query<(&Unit, &Position, &Blocker)>,
+    board: &Board,
+    blockers: &Vec<(&Position, &Blocker)>
+) -> Vec<(Vector2Int, usize)> {
+    let mut threats: Vec<(Vector2Int, usize)> = Vec::new();
+    for (unit, position, _) in unit_query.iter() {
+        if let UnitKind::Player = unit.kind { continue; }
+        if !unit.can_act_next_turn() { continue; }
+
+        let range = unit.effects.restrict_moves(
+            unit.behaviour.possible_positions(position.v, board, blockers),
+            blockers
+        );
+        for v in range {
+            match threats.iter_mut().find(|(t, _)| *t == v) {
+                Some(threat) => threat.1 += 1,
+                None => threats.push((v, 1))
+            }
+        }
+    }
+    threats
+}
+
+fn spawn_cursor(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    range: &Vec<Vector2Int>,
+    material: Handle<ColorMaterial>,
+    z: f32
+) {
+    let mesh = create_cursor_mesh(range);
+
+    commands.spawn_bundle(sprite::MaterialMesh2dBundle {
+        mesh: sprite::Mesh2dHandle(meshes.add(mesh)),
+        material,
+        transform: Transform::default()
+            .with_scale(Vec3::new(TILE_SIZE, TILE_SIZE, 0.0))
+            .with_translation(Vec3::new(0., 0., z)),
+        ..Default::default()
+    })
+    .insert(Cursor);
+}
+
 pub fn clear_cursor(
     mut commands: Commands,
     query: Query<Entity, With<Cursor>>,
@@ -104,23 +204,62 @@ pub fn load_assets(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    mut asset_list: ResMut<crate::assets::AssetList>    
+    mut asset_list: ResMut<crate::assets::AssetList>,
+    palette: Res<Palette>
 ) {
-    let texture_handle = asset_server.load("cursor.png");
-    asset_list.0.push(texture_handle.clone_untyped());
+    // every kind of square has its own shape, so it doesn't rely on the color alone
+    let mut load_material = |path: &str, color: Color| {
+        let texture_handle = asset_server.load(path);
+        asset_list.0.push(texture_handle.clone_untyped());
+        materials.add(ColorMaterial { color, texture: Some(texture_handle) })
+    };
 
-    let color = Color::Rgba { red: 0.84, green: 0.85, blue: 0.84, alpha: 1. };
-    let material_handle = materials.add(
-        ColorMaterial{ color: color, texture: Some(texture_handle)}
-    );
+    let material_handle = load_material("cursor.png", palette.cursor);
+    let capture_material_handle = load_material("cursor_capture.png", palette.capture);
+    let npc_material_handle = load_material("cursor_dotted.png", palette.npc_cursor);
+    let target_material_handle = load_material("cursor_small.png", palette.target);
+    let danger_materials = DANGER_ALPHA.iter()
+        .map(|alpha| load_material("cursor_danger.png", *palette.danger.clone().set_a(*alpha)))
+        .collect();
 
     commands.insert_resource(
         CursorAssets { 
             material: material_handle,
+            capture_material: capture_material_handle,
+            npc_material: npc_material_handle,
+            target_material: target_material_handle,
+            danger_materials
         }
     );
 }
 
+pub fn apply_palette(
+    palette: Res<Palette>,
+    assets: Option<Res<CursorAssets>>,
+    mut materials: ResMut<Assets<ColorMaterial>>
+) {
+    let assets = match assets {
+        Some(a) => a,
+        None => return
+    };
+    if !palette.is_changed() { return; }
+
+    let mut colors = vec![
+        (&assets.material, palette.cursor),
+        (&assets.capture_material, palette.capture),
+        (&assets.npc_material, palette.npc_cursor),
+        (&assets.target_material, palette.target)
+    ];
+    for (handle, alpha) in assets.danger_materials.iter().zip(DANGER_ALPHA) {
+        colors.push((handle, *palette.danger.clone().set_a(alpha)));
+    }
+    for (handle, color) in colors {
+        if let Some(material) = materials.get_mut(handle) {
+            material.color = color;
+        }
+    }
+}
+
 fn create_cursor_mesh(
     positions: &Vec<Vector2Int>
 ) -> Mesh {
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
+use crate::actions::{Action, Actions};
 use crate::board::{Board, Blocker, Position};
+use crate::graphics::palette::Palette;
+use crate::items::{ItemTargeting, TargetingEvent};
 use crate::units::{
     Unit,
     npc::NPC,
@@ -9,7 +12,7 @@ use crate::units::{
 use crate::states::{FadeState, GameState};
 use crate::vectors::Vector2Int;
 
-use super::player_menu::PlayerButtonClickEvent;
+use super::player_menu::{ButtonAction, PlayerButtonClickEvent};
 
 pub struct InputAssets {
     pub selected_npc: Option<Entity>,
@@ -26,28 +29,19 @@ pub fn reset_input_assets(
 }
 
 
-pub fn mouse_press_menu(
-    mut buttons: ResMut<Input<MouseButton>>,
-    mut game_state: ResMut<State<GameState>>
-) {
-    if buttons.just_pressed(MouseButton::Left) {
-        buttons.clear();
-        game_state.set(GameState::MapGenerate);
-    }
-}
-
+// game over and the menu screens that only show information lead back to the main menu
 pub fn mouse_press_game_over(
-    mut buttons: ResMut<Input<MouseButton>>,
+    mut actions: ResMut<Actions>,
     mut game_state: ResMut<State<GameState>>
 ) {
-    if buttons.just_pressed(MouseButton::Left) {
-        buttons.clear();
+    if [Action::Move, Action::Confirm, Action::Back].iter().any(|a| actions.just_pressed(*a)) {
+        actions.clear();
         game_state.set(GameState::MainMenu);
     }
 }
 
 pub fn mouse_press_game(
-    buttons: Res<Input<MouseButton>>,
+    actions: Res<Actions>,
     windows: Res<Windows>,
     camera_query: Query<(&Camera, &GlobalTransform), With<crate::camera::MainCamera>>,
     mut ev_player: EventWriter<MovePlayerEvent>,
@@ -56,6 +50,10 @@ pub fn mouse_press_game(
     mut ev_ui: EventWriter<super::RedrawUIEvent>,
     mut interactions: Query<(&Interaction, Entity, &mut UiColor), (Changed<Interaction>, With<Button>)>,
     mut ev_player_button: EventWriter<PlayerButtonClickEvent>,
+    targeting: Res<ItemTargeting>,
+    mut ev_targeting: EventWriter<TargetingEvent>,
+    button_query: Query<(Entity, &Interaction), With<Button>>,
+    palette: Res<Palette>
 ) {
     let mut menu_clicked = false;
     for (interaction, entity, mut color) in interactions.iter_mut() {
@@ -63,31 +61,52 @@ pub fn mouse_press_game(
             Interaction::Clicked => {
                 menu_clicked = true;
                 assets.clicked_button = Some(entity);
-                *color =super::player_menu::BUTTON_COLOR_CLICK.into();
+                *color = palette.button_click.into();
             },
             Interaction::None => {
                 assets.clicked_button = None;
-                *color = super::player_menu::BUTTON_COLOR.into();
+                *color = palette.button.into();
             }
             _ => {}
         }
     }
 
-    if buttons.just_pressed(MouseButton::Left) && !menu_clicked {        
+    // moves with the board cursor and the cancel are handled in keyboard_game
+    if actions.just_pressed_by_pointer(Action::Move) && !menu_clicked {
         if let Some(world_pos) = mouse_to_world(&windows, &camera_query) {
             let v = Vector2Int::from_world(world_pos.x, world_pos.y);
-            ev_player.send(MovePlayerEvent(v));
+            match targeting.is_active() {
+                // the board is used to pick the item's target instead of moving
+                true => ev_targeting.send(TargetingEvent::Select(v)),
+                false => ev_player.send(MovePlayerEvent(v))
+            }
         };
     }
 
-    if buttons.just_released(MouseButton::Left) {
+    if actions.just_released(Action::Move) {
         if let Some(entity) = assets.clicked_button {
             assets.clicked_button = None; 
-            ev_player_button.send(PlayerButtonClickEvent(entity));
+            let action = match actions.pressed(Action::Reorder) {
+                true => ButtonAction::Move,
+                false => ButtonAction::Use
+            };
+            ev_player_button.send(PlayerButtonClickEvent(entity, action));
         }
     }
 
-    if buttons.just_pressed(MouseButton::Right) { 
+    let hovered_button = button_query.iter()
+        .find(|(_, i)| **i != Interaction::None)
+        .map(|(e, _)| e);
+
+    // a hovered card takes the press before the board inspect
+    if actions.just_pressed(Action::Discard) {
+        if let Some(entity) = hovered_button {
+            ev_player_button.send(PlayerButtonClickEvent(entity, ButtonAction::Discard));
+            return;
+        }
+    }
+
+    if actions.just_pressed(Action::Inspect) && !targeting.is_active() { 
         if let Some(world_pos) = mouse_to_world(&windows, &camera_query) {
             let v = Vector2Int::from_world(world_pos.x, world_pos.y);
             for (entity, position) in unit_query.iter() {
@@ -103,14 +122,25 @@ pub fn mouse_press_game(
 
     }
 
-    if buttons.just_released(MouseButton::Right) {
+    if actions.just_released(Action::Inspect) {
         assets.selected_npc = None; 
         ev_ui.send(super::RedrawUIEvent);
     }
 }
 
 
-fn mouse_to_world(
+pub fn toggle_danger_map(
+    actions: Res<Actions>,
+    mut danger_map: ResMut<super::cursor::DangerMap>,
+    mut ev_ui: EventWriter<super::RedrawUIEvent>
+) {
+    if actions.just_pressed(Action::DangerMap) {
+        danger_map.visible = !danger_map.visible;
+        ev_ui.send(super::RedrawUIEvent);
+    }
+}
+
+pub fn mouse_to_world(
     windows: &Res<Windows>,
     camera_query: &Query<(&Camera, &GlobalTransform), With<crate::camera::MainCamera>>,
 ) -> Option<Vec2> {
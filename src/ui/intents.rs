@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use crate::board::{Blocker, Board, Position};
+use crate::graphics::{
+    TILE_SIZE, UNIT_Z,
+    unit_renderer::{UnitSprites, get_sprite_idx}
+};
+use crate::units::{
+    Unit,
+    npc::{NPC, SpawnOrder, get_turn_order, plan_npc_turn},
+    player::{Player, PlayerData}
+};
+
+const GHOST_COLOR: Color = Color::Rgba { red: 1., green: 1., blue: 1., alpha: 0.4 };
+
+// semi-transparent copy of an npc on the tile it is going to move to
+#[derive(Component)]
+pub struct IntentGhost;
+
+pub fn clear_intents(
+    mut commands: Commands,
+    query: Query<Entity, With<IntentGhost>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn draw_intents(
+    mut commands: Commands,
+    mut ev_redraw: EventReader<super::RedrawUIEvent>,
+    ghost_query: Query<Entity, With<IntentGhost>>,
+    npc_query: Query<(Entity, &Unit, &SpawnOrder), With<NPC>>,
+    position_query: Query<&Position>,
+    wall_query: Query<(&Position, &Blocker), Without<Unit>>,
+    player_query: Query<&Position, With<Player>>,
+    board_query: Query<&Board>,
+    player_data: Res<PlayerData>,
+    sprites: Res<UnitSprites>
+) {
+    if ev_redraw.iter().last().is_none() { return; }
+
+    for entity in ghost_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let board = match board_query.get_single() {
+        Ok(b) => b,
+        _ => return
+    };
+
+    let npcs: Vec<(Entity, &Unit, _)> = get_turn_order(npc_query.iter())
+        .into_iter()
+        .filter_map(|e| {
+            let (_, unit, _) = npc_query.get(e).ok()?;
+            Some((e, unit, position_query.get(e).ok()?.v))
+        })
+        .collect();
+    let walls: Vec<_> = wall_query.iter().collect();
+    let player = player_query.get_single().ok().map(|p| p.v);
+
+    for planned in plan_npc_turn(&npcs, &walls, player, player_data.armor, board) {
+        let mut sprite = TextureAtlasSprite::new(get_sprite_idx(&planned.kind));
+        sprite.custom_size = Some(Vec2::splat(TILE_SIZE));
+        sprite.color = GHOST_COLOR;
+        commands.spawn_bundle(SpriteSheetBundle {
+                sprite,
+                texture_atlas: sprites.0.clone(),
+                transform: Transform::from_translation(Vec3::new(
+                    (planned.to.x as f32 + 0.5) * TILE_SIZE,
+                    (planned.to.y as f32 + 0.5) * TILE_SIZE,
+                    UNIT_Z + 1.
+                )),
+                ..Default::default()
+            })
+            .insert(IntentGhost);
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::graphics::palette::Palette;
+use crate::items::{
+    data::get_item_label,
+    shop::{Interlude, InterludeEvent, InterludeKind}
+};
+use crate::units::player::PlayerData;
+
+use super::player_menu::{TRANSPARENT, PlayerMenuAssets};
+
+const BACKGROUND_COLOR: Color = Color::Rgba { red: 0.11, green: 0.09, blue: 0.08, alpha: 1. };
+const CARD_WIDTH: Val = Val::Px(96.);
+const CARD_HEIGHT: Val = Val::Px(144.);
+const CARD_MARGIN: Val = Val::Px(16.);
+
+#[derive(Component)]
+pub struct InterludeMenu;
+
+// None is the button that leaves the interlude
+#[derive(Component)]
+pub struct InterludeButton(pub Option<usize>);
+
+pub fn clear_interlude(
+    mut commands: Commands,
+    query: Query<Entity, With<InterludeMenu>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity)
+            .despawn_recursive()
+    }
+}
+
+pub fn draw_interlude(
+    mut commands: Commands,
+    mut ev_redraw_ui: EventReader<super::RedrawUIEvent>,
+    query: Query<Entity, With<InterludeMenu>>,
+    interlude: Option<Res<Interlude>>,
+    player_data: Res<PlayerData>,
+    font_assets: Res<super::FontAssets>,
+    assets: Res<PlayerMenuAssets>,
+    palette: Res<Palette>
+) {
+    let interlude = match interlude {
+        Some(i) => i,
+        None => return
+    };
+    for _ in ev_redraw_ui.iter() {
+        for entity in query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let title = match interlude.kind {
+            InterludeKind::Reward => "Choose your reward",
+            InterludeKind::Shop => "Shop"
+        };
+        let text_style = TextStyle {
+            color: palette.status,
+            font: font_assets.font.clone(),
+            font_size: 32.,
+        };
+
+        commands.spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                    flex_direction: FlexDirection::ColumnReverse,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                },
+                color: BACKGROUND_COLOR.into(),
+                ..Default::default()
+            })
+            .insert(InterludeMenu)
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle::from_section(title, text_style.clone()));
+                parent.spawn_bundle(TextBundle::from_section(
+                    format!("Coins: {}", player_data.coins),
+                    TextStyle { font_size: 24., ..text_style.clone() }
+                ));
+                parent.spawn_bundle(NodeBundle {
+                    style: Style {
+                        margin: UiRect::all(CARD_MARGIN),
+                        ..Default::default()
+                    },
+                    color: TRANSPARENT.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for (idx, offer) in interlude.offers.iter().enumerate() {
+                        if offer.taken { continue; }
+                        parent.spawn_bundle(ButtonBundle {
+                            style: Style {
+                                size: Size::new(CARD_WIDTH, CARD_HEIGHT),
+                                margin: UiRect::all(CARD_MARGIN),
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                flex_direction: FlexDirection::ColumnReverse,
+                                ..Default::default()
+                            },
+                            color: palette.button.into(),
+                            image: UiImage(assets.button_image.clone()),
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn_bundle(ImageBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(64.), Val::Px(64.)),
+                                    ..Default::default()
+                                },
+                                color: palette.button_text.into(),
+                                image: UiImage(assets.item_images[&offer.kind].clone()),
+                                focus_policy: FocusPolicy::Pass,
+                                ..Default::default()
+                            });
+                            parent.spawn_bundle(TextBundle::from_section(
+                                format!("{:?}", offer.kind),
+                                TextStyle { color: palette.button_text, font_size: 16., ..text_style.clone() }
+                            ));
+                            parent.spawn_bundle(TextBundle::from_section(
+                                get_item_label(offer.kind),
+                                TextStyle { color: palette.button_text, font_size: 24., ..text_style.clone() }
+                            ));
+                            if offer.price > 0 {
+                                parent.spawn_bundle(TextBundle::from_section(
+                                    format!("${}", offer.price),
+                                    TextStyle { color: palette.button_text, font_size: 24., ..text_style.clone() }
+                                ));
+                            }
+                        })
+                        .insert(InterludeButton(Some(idx)));
+                    }
+                });
+                parent.spawn_bundle(ButtonBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(8.)),
+                        ..Default::default()
+                    },
+                    color: palette.button.into(),
+                    image: UiImage(assets.button_image.clone()),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    let label = match interlude.kind {
+                        InterludeKind::Reward => "Skip",
+                        InterludeKind::Shop => "Continue"
+                    };
+                    parent.spawn_bundle(TextBundle::from_section(
+                        label,
+                        TextStyle { color: palette.button_text, ..text_style.clone() }
+                    ));
+                })
+                .insert(InterludeButton(None));
+            });
+    }
+}
+
+pub fn interlude_click(
+    mut interactions: Query<(&Interaction, &InterludeButton, &mut UiColor), Changed<Interaction>>,
+    mut ev_interlude: EventWriter<InterludeEvent>,
+    palette: Res<Palette>
+) {
+    for (interaction, button, mut color) in interactions.iter_mut() {
+        match *interaction {
+            Interaction::Clicked => {
+                *color = palette.button_click.into();
+                match button.0 {
+                    Some(idx) => ev_interlude.send(InterludeEvent::Take(idx)),
+                    None => ev_interlude.send(InterludeEvent::Continue)
+                }
+            },
+            _ => *color = palette.button.into()
+        }
+    }
+}
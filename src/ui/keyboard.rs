@@ -0,0 +1,388 @@
+use bevy::prelude::*;
+
+use crate::actions::{Action, Actions, Binding};
+use crate::board::{Board, Position};
+use crate::command::{CommandEvent, CommandType};
+use crate::graphics::{TILE_SIZE, CURSOR_Z, palette::Palette};
+use crate::items::{
+    InventoryEvent, InventoryPrompt, ItemTargeting, TargetingEvent, UseItemEvent,
+    shop::{Interlude, InterludeEvent}
+};
+use crate::states::AnimationState;
+use crate::units::{
+    Unit,
+    npc::{NPC, SpawnOrder, get_turn_order},
+    player::{MovePlayerEvent, Player}
+};
+use crate::vectors::Vector2Int;
+
+use super::interlude::InterludeButton;
+
+const CURSOR_ALPHA: f32 = 0.5;
+
+// tile picked with the keyboard or the gamepad
+#[derive(Default)]
+pub struct BoardCursor {
+    pub v: Option<Vector2Int>
+}
+
+#[derive(Component)]
+pub struct BoardCursorMarker;
+
+// offer index of the focused interlude button, None is the leave button
+#[derive(Default)]
+pub struct InterludeFocus(pub Option<usize>);
+
+pub fn keyboard_game(
+    mut actions: ResMut<Actions>,
+    mut cursor: ResMut<BoardCursor>,
+    mut input_assets: ResMut<super::input::InputAssets>,
+    targeting: Res<ItemTargeting>,
+    prompt: Res<InventoryPrompt>,
+    animation_state: Res<State<AnimationState>>,
+    mut ev_move: EventWriter<MovePlayerEvent>,
+    mut ev_targeting: EventWriter<TargetingEvent>,
+    mut ev_use_item: EventWriter<UseItemEvent>,
+    mut ev_inventory: EventWriter<InventoryEvent>,
+    mut ev_ui: EventWriter<super::RedrawUIEvent>,
+    mut ev_command: EventWriter<CommandEvent>,
+    player_query: Query<(Entity, &Position), With<Player>>,
+    npc_query: Query<(Entity, &Unit, &SpawnOrder), With<NPC>>,
+    board_query: Query<&Board>
+) {
+    let pressed: Vec<(Action, Binding)> = actions.iter_just_pressed().collect();
+    for (action, binding) in pressed {
+        // an earlier action may have consumed the binding
+        if !actions.iter_just_pressed().any(|p| p == (action, binding)) { continue; }
+        let pointer = matches!(binding, Binding::Mouse(_));
+        match action {
+            Action::Up | Action::Down | Action::Left | Action::Right => {
+                let board = match board_query.get_single() {
+                    Ok(b) => b,
+                    _ => continue
+                };
+                let current = match cursor.v.or_else(|| player_query.get_single().ok().map(|(_, p)| p.v)) {
+                    Some(v) => v,
+                    None => continue
+                };
+                let dir = match action {
+                    Action::Up => Vector2Int::new(0, 1),
+                    Action::Down => Vector2Int::new(0, -1),
+                    Action::Left => Vector2Int::new(-1, 0),
+                    _ => Vector2Int::new(1, 0)
+                };
+                // the first press only shows the cursor on the player
+                let v = match cursor.v {
+                    Some(_) => current + dir,
+                    None => current
+                };
+                if board.tiles.contains_key(&v) { cursor.v = Some(v); }
+            },
+            // pointer moves are handled in mouse_press_game
+            Action::Move if !pointer => {
+                let v = match cursor.v {
+                    Some(v) => v,
+                    None => continue
+                };
+                match targeting.is_active() {
+                    true => ev_targeting.send(TargetingEvent::Select(v)),
+                    false => ev_move.send(MovePlayerEvent(v))
+                }
+            },
+            Action::Confirm if targeting.is_active() => ev_targeting.send(TargetingEvent::Confirm),
+            // only the innermost thing is backed out of, the pause menu runs after this
+            Action::Back => {
+                if targeting.is_active() {
+                    ev_targeting.send(TargetingEvent::Cancel);
+                } else if prompt.item.is_some() {
+                    ev_inventory.send(InventoryEvent::Leave);
+                } else if cursor.v.is_some() {
+                    cursor.v = None;
+                } else {
+                    continue;
+                }
+                actions.consume(binding);
+            },
+            Action::InspectNext => {
+                let order = get_turn_order(npc_query.iter());
+                input_assets.selected_npc = match input_assets.selected_npc {
+                    // after the last npc the player's range is shown again
+                    Some(e) => order.iter()
+                        .skip_while(|o| **o != e)
+                        .nth(1)
+                        .copied(),
+                    None => order.first().copied()
+                };
+                ev_ui.send(super::RedrawUIEvent);
+            },
+            Action::UseItem(idx) => {
+                match prompt.item {
+                    Some(_) => ev_inventory.send(InventoryEvent::Swap(idx)),
+                    None => ev_use_item.send(UseItemEvent(idx))
+                }
+            },
+            // like a move, waiting has to wait for the last animation to end
+            Action::Wait if animation_state.current() == &AnimationState::Idle => {
+                if let Ok((entity, _)) = player_query.get_single() {
+                    ev_command.send(CommandEvent(CommandType::RemoveAP(entity)));
+                }
+            },
+            _ => ()
+        }
+    }
+}
+
+pub fn draw_board_cursor(
+    mut commands: Commands,
+    cursor: Res<BoardCursor>,
+    palette: Res<Palette>,
+    mut marker_query: Query<(&mut Transform, &mut Visibility, &mut Sprite), With<BoardCursorMarker>>
+) {
+    let (mut transform, mut visibility, mut sprite) = match marker_query.get_single_mut() {
+        Ok(m) => m,
+        _ => {
+            commands.spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: *palette.target.clone().set_a(CURSOR_ALPHA),
+                        custom_size: Some(Vec2::splat(TILE_SIZE)),
+                        ..Default::default()
+                    },
+                    visibility: Visibility { is_visible: false },
+                    ..Default::default()
+                })
+                .insert(BoardCursorMarker);
+            return;
+        }
+    };
+    if palette.is_changed() { sprite.color = *palette.target.clone().set_a(CURSOR_ALPHA); }
+    visibility.is_visible = cursor.v.is_some();
+    if let Some(v) = cursor.v {
+        transform.translation = Vec3::new(
+            (v.x as f32 + 0.5) * TILE_SIZE,
+            (v.y as f32 + 0.5) * TILE_SIZE,
+            CURSOR_Z + 1.
+        );
+    }
+}
+
+pub fn clear_board_cursor(
+    mut commands: Commands,
+    query: Query<Entity, With<BoardCursorMarker>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn keyboard_interlude(
+    actions: Res<Actions>,
+    mut focus: ResMut<InterludeFocus>,
+    interlude: Option<Res<Interlude>>,
+    mut ev_interlude: EventWriter<InterludeEvent>,
+    mut button_query: Query<(&InterludeButton, &mut UiColor)>,
+    palette: Res<Palette>
+) {
+    let interlude = match interlude {
+        Some(i) => i,
+        None => return
+    };
+    let pressed: Vec<Action> = actions.iter_just_pressed()
+        .filter(|(_, b)| !matches!(b, Binding::Mouse(_)))
+        .map(|(a, _)| a)
+        .collect();
+    if pressed.is_empty() { return; }
+
+    // open offers followed by the leave button
+    let buttons: Vec<Option<usize>> = interlude.offers.iter()
+        .enumerate()
+        .filter(|(_, o)| !o.taken)
+        .map(|(idx, _)| Some(idx))
+        .chain([None])
+        .collect();
+    let mut current = buttons.iter().position(|b| *b == focus.0).unwrap_or(0);
+
+    for action in pressed {
+        match action {
+            Action::Left | Action::Up => current = (current + buttons.len() - 1) % buttons.len(),
+            Action::Right | Action::Down => current = (current + 1) % buttons.len(),
+            Action::Move | Action::Confirm => match buttons[current] {
+                Some(idx) => ev_interlude.send(InterludeEvent::Take(idx)),
+                None => ev_interlude.send(InterludeEvent::Continue)
+            },
+            Action::Back => ev_interlude.send(InterludeEvent::Continue),
+            _ => ()
+        }
+    }
+    focus.0 = buttons[current];
+
+    for (button, mut color) in button_query.iter_mut() {
+        *color = match button.0 == focus.0 {
+            true => palette.button_click.into(),
+            false => palette.button.into()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::gamepad::{GamepadEvent, GamepadEventType, gamepad_connection_system};
+    use std::collections::HashMap;
+
+    use crate::actions::{InputBindings, update_actions};
+    use super::*;
+
+    fn setup(bindings: InputBindings) -> App {
+        let mut app = App::new();
+        app.insert_resource(bindings);
+        app.init_resource::<Input<KeyCode>>();
+        app.init_resource::<Input<MouseButton>>();
+        app.init_resource::<Gamepads>();
+        app.init_resource::<Input<GamepadButton>>();
+        app.init_resource::<Actions>();
+        app.init_resource::<BoardCursor>();
+        app.insert_resource(super::super::input::InputAssets { selected_npc: None, clicked_button: None });
+        app.init_resource::<ItemTargeting>();
+        app.init_resource::<InventoryPrompt>();
+        app.insert_resource(State::new(AnimationState::Idle));
+        app.add_event::<GamepadEvent>();
+        app.add_event::<MovePlayerEvent>();
+        app.add_event::<TargetingEvent>();
+        app.add_event::<UseItemEvent>();
+        app.add_event::<InventoryEvent>();
+        app.add_event::<super::super::RedrawUIEvent>();
+        app.add_event::<CommandEvent>();
+        app.add_system(gamepad_connection_system.before(update_actions));
+        app.add_system(update_actions);
+        app.add_system(keyboard_game.after(update_actions));
+
+        // 3x3 board with the player in the middle
+        let tiles: HashMap<Vector2Int, Entity> = (0..9)
+            .map(|idx| (Vector2Int::new(idx % 3, idx / 3), app.world.spawn().id()))
+            .collect();
+        app.world.spawn().insert(Board { tiles, stair_v: Vector2Int::new(2, 2) });
+        app.world.spawn().insert(Player).insert(Position { v: Vector2Int::new(1, 1) });
+        app
+    }
+
+    fn command_events(app: &App) -> Vec<CommandType> {
+        let events = app.world.resource::<Events<CommandEvent>>();
+        events.get_reader().iter(events).map(|ev| ev.0).collect()
+    }
+
+    // runs a frame with the key held down since the start of the frame
+    fn press_key(app: &mut App, key: KeyCode) {
+        app.world.resource_mut::<Input<KeyCode>>().press(key);
+        app.update();
+        let mut keys = app.world.resource_mut::<Input<KeyCode>>();
+        keys.release(key);
+        keys.clear();
+    }
+
+    fn move_events(app: &App) -> Vec<Vector2Int> {
+        let events = app.world.resource::<Events<MovePlayerEvent>>();
+        events.get_reader().iter(events).map(|ev| ev.0).collect()
+    }
+
+    fn targeting_events(app: &App) -> Vec<TargetingEvent> {
+        let events = app.world.resource::<Events<TargetingEvent>>();
+        events.get_reader().iter(events).copied().collect()
+    }
+
+    #[test]
+    fn cursor_starts_on_player_and_moves() {
+        let mut app = setup(InputBindings::default());
+        press_key(&mut app, KeyCode::Right);
+        assert_eq!(app.world.resource::<BoardCursor>().v, Some(Vector2Int::new(1, 1)));
+        press_key(&mut app, KeyCode::Right);
+        assert_eq!(app.world.resource::<BoardCursor>().v, Some(Vector2Int::new(2, 1)));
+        // the cursor stays on the board
+        press_key(&mut app, KeyCode::Right);
+        assert_eq!(app.world.resource::<BoardCursor>().v, Some(Vector2Int::new(2, 1)));
+        press_key(&mut app, KeyCode::Space);
+        assert_eq!(move_events(&app), vec![Vector2Int::new(2, 1)]);
+    }
+
+    #[test]
+    fn move_without_cursor_is_ignored() {
+        let mut app = setup(InputBindings::default());
+        press_key(&mut app, KeyCode::Space);
+        assert!(move_events(&app).is_empty());
+    }
+
+    #[test]
+    fn gamepad_moves_the_cursor() {
+        let mut app = setup(InputBindings::default());
+        let gamepad = Gamepad::new(0);
+        app.world.resource_mut::<Events<GamepadEvent>>()
+            .send(GamepadEvent::new(gamepad, GamepadEventType::Connected));
+        app.update();
+
+        for button in [GamepadButtonType::DPadUp, GamepadButtonType::DPadUp, GamepadButtonType::South] {
+            let button = GamepadButton::new(gamepad, button);
+            app.world.resource_mut::<Input<GamepadButton>>().press(button);
+            app.update();
+            let mut buttons = app.world.resource_mut::<Input<GamepadButton>>();
+            buttons.release(button);
+            buttons.clear();
+        }
+        assert_eq!(move_events(&app), vec![Vector2Int::new(1, 2)]);
+    }
+
+    #[test]
+    fn targeting_uses_the_cursor() {
+        let mut app = setup(InputBindings::default());
+        app.world.resource_mut::<ItemTargeting>().item = Some(0);
+        press_key(&mut app, KeyCode::Up);
+        press_key(&mut app, KeyCode::Space);
+        press_key(&mut app, KeyCode::Return);
+        assert!(move_events(&app).is_empty());
+        assert_eq!(
+            targeting_events(&app),
+            vec![TargetingEvent::Select(Vector2Int::new(1, 1)), TargetingEvent::Confirm]
+        );
+    }
+
+    #[test]
+    fn escape_cancels_targeting_before_pause() {
+        // escape bound to both back and pause
+        let mut bindings = InputBindings::default();
+        for (action, action_bindings) in bindings.0.iter_mut() {
+            if *action == Action::Pause { action_bindings.push(Binding::Key(KeyCode::Escape)); }
+        }
+        let mut app = setup(bindings);
+        app.world.resource_mut::<ItemTargeting>().item = Some(0);
+
+        app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+        app.update();
+        assert_eq!(targeting_events(&app), vec![TargetingEvent::Cancel]);
+        assert!(!app.world.resource::<Actions>().just_pressed(Action::Pause));
+    }
+
+    #[test]
+    fn escape_with_nothing_to_cancel_pauses() {
+        let mut bindings = InputBindings::default();
+        for (action, action_bindings) in bindings.0.iter_mut() {
+            if *action == Action::Pause { action_bindings.push(Binding::Key(KeyCode::Escape)); }
+        }
+        let mut app = setup(bindings);
+        app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+        app.update();
+        assert!(targeting_events(&app).is_empty());
+        assert!(app.world.resource::<Actions>().just_pressed(Action::Pause));
+    }
+
+    #[test]
+    fn wait_removes_ap() {
+        let mut app = setup(InputBindings::default());
+        press_key(&mut app, KeyCode::W);
+        assert!(matches!(command_events(&app)[..], [CommandType::RemoveAP(_)]));
+    }
+
+    #[test]
+    fn wait_is_ignored_while_animating() {
+        let mut app = setup(InputBindings::default());
+        app.insert_resource(State::new(AnimationState::Animating));
+        press_key(&mut app, KeyCode::W);
+        assert!(command_events(&app).is_empty());
+    }
+}
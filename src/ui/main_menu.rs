@@ -1,8 +1,75 @@
+use bevy::app::AppExit;
 use bevy::prelude::*;
 
+use crate::actions::{Action, Actions, Binding, InputBindings, get_help_text};
+use crate::graphics::palette::Palette;
+use crate::run::{PendingFloor, RunRng, continue_run, get_daily_seed, get_random_seed, load_saved_run, new_run};
+use crate::states::GameState;
+use crate::units::player::PlayerData;
+
+use super::player_menu::{PlayerMenuAssets, TRANSPARENT};
+
+const DISABLED_COLOR: Color = Color::Rgba { red: 0.4, green: 0.4, blue: 0.4, alpha: 1. };
+const MAX_SEED_LENGTH: usize = 18;
+
 #[derive(Component)]
 pub struct MainMenu;
 
+#[derive(Clone, Copy, Component, PartialEq, Eq)]
+pub enum MenuButton {
+    NewRun,
+    // resumes the saved run where it was left, or at the start of its last floor
+    Continue,
+    DailySeed,
+    CustomSeed,
+    Codex,
+    Achievements,
+    Statistics,
+    Settings,
+    Quit
+}
+
+const MENU_BUTTONS: [MenuButton; 9] = [
+    MenuButton::NewRun,
+    MenuButton::Continue,
+    MenuButton::DailySeed,
+    MenuButton::CustomSeed,
+    MenuButton::Codex,
+    MenuButton::Achievements,
+    MenuButton::Statistics,
+    MenuButton::Settings,
+    MenuButton::Quit
+];
+
+// continue without a saved run
+#[derive(Component)]
+pub struct Disabled;
+
+#[derive(Component)]
+pub struct SeedPrompt;
+
+// index of the focused button in MENU_BUTTONS
+#[derive(Default)]
+pub struct MenuFocus(pub usize);
+
+// digits typed so far, None while the seed prompt is closed
+#[derive(Default)]
+pub struct SeedEntry(pub Option<String>);
+
+fn get_button_label(button: MenuButton) -> &'static str {
+    match button {
+        MenuButton::NewRun => "New Run",
+        MenuButton::Continue => "Continue",
+        MenuButton::DailySeed => "Daily Seed",
+        MenuButton::CustomSeed => "Custom Seed",
+        MenuButton::Codex => "Codex",
+        MenuButton::Achievements => "Achievements",
+        MenuButton::Statistics => "Statistics",
+        MenuButton::Settings => "Settings",
+        MenuButton::Quit => "Quit"
+    }
+}
+
 pub fn clear_menu(
     mut commands: Commands,
     query: Query<Entity, With<MainMenu>>,
@@ -15,9 +82,14 @@ pub fn clear_menu(
 
 pub fn draw_menu(
     mut commands: Commands,
-    assets: Res<super::FontAssets>
+    mut seed_entry: ResMut<SeedEntry>,
+    assets: Res<super::FontAssets>,
+    menu_assets: Res<PlayerMenuAssets>,
+    bindings: Res<InputBindings>,
+    palette: Res<Palette>
 ) {
-    let text = "Left click to move\nRight hold to see other's moves";
+    seed_entry.0 = None;
+    let text = format!("{}\nControls can be changed in input.ron", get_help_text(&bindings));
 
     commands
             .spawn_bundle(TextBundle {
@@ -35,11 +107,182 @@ pub fn draw_menu(
                     TextStyle {
                         color: Color::WHITE,
                         font: assets.font.clone(),
-                        font_size: 32.,
+                        font_size: 16.,
                         ..Default::default()
                     }
                 ),
                 ..Default::default()
             })
             .insert(MainMenu);
-}
\ No newline at end of file
+
+    let has_saved_run = load_saved_run().is_some();
+
+    commands.spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(40.),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.), Val::Undefined),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: TRANSPARENT.into(),
+            ..Default::default()
+        })
+        .insert(MainMenu)
+        .with_children(|parent| {
+            for button in MENU_BUTTONS {
+                let disabled = button == MenuButton::Continue && !has_saved_run;
+                let mut node = parent.spawn_bundle(ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(8.)),
+                            margin: UiRect::new(Val::Px(0.), Val::Px(0.), Val::Px(0.), Val::Px(8.)),
+                            ..Default::default()
+                        },
+                        color: palette.button.into(),
+                        image: UiImage(menu_assets.button_image.clone()),
+                        ..Default::default()
+                    });
+                node.insert(button)
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle::from_section(
+                            get_button_label(button),
+                            TextStyle {
+                                color: match disabled {
+                                    true => DISABLED_COLOR,
+                                    false => palette.button_text
+                                },
+                                font: assets.font.clone(),
+                                font_size: 32.
+                            }
+                        ));
+                    });
+                if disabled { node.insert(Disabled); }
+            }
+            parent.spawn_bundle(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        color: palette.status,
+                        font: assets.font.clone(),
+                        font_size: 24.
+                    }
+                ))
+                .insert(SeedPrompt);
+        });
+}
+
+pub fn menu_navigation(
+    mut actions: ResMut<Actions>,
+    mut focus: ResMut<MenuFocus>,
+    mut seed_entry: ResMut<SeedEntry>,
+    mut game_state: ResMut<State<GameState>>,
+    mut run_rng: ResMut<RunRng>,
+    mut player_data: ResMut<PlayerData>,
+    mut pending_floor: ResMut<PendingFloor>,
+    mut ev_exit: EventWriter<AppExit>,
+    mut button_query: Query<(&MenuButton, &Interaction, &mut UiColor, Option<&Disabled>)>,
+    palette: Res<Palette>
+) {
+    if seed_entry.0.is_some() { return; }
+
+    let mut activated = None;
+    for (button, interaction, _, _) in button_query.iter() {
+        let idx = MENU_BUTTONS.iter().position(|b| b == button).unwrap_or(0);
+        match *interaction {
+            Interaction::Hovered => focus.0 = idx,
+            Interaction::Clicked if actions.just_pressed(Action::Move) => activated = Some(*button),
+            _ => ()
+        }
+    }
+    for (action, binding) in actions.iter_just_pressed() {
+        match action {
+            Action::Up => focus.0 = (focus.0 + MENU_BUTTONS.len() - 1) % MENU_BUTTONS.len(),
+            Action::Down => focus.0 = (focus.0 + 1) % MENU_BUTTONS.len(),
+            Action::Confirm => activated = Some(MENU_BUTTONS[focus.0]),
+            Action::Move if !matches!(binding, Binding::Mouse(_)) => activated = Some(MENU_BUTTONS[focus.0]),
+            _ => ()
+        }
+    }
+
+    for (button, _, mut color, _) in button_query.iter_mut() {
+        *color = match MENU_BUTTONS[focus.0] == *button {
+            true => palette.button_click.into(),
+            false => palette.button.into()
+        };
+    }
+
+    let button = match activated {
+        Some(b) => b,
+        None => return
+    };
+    if button_query.iter().any(|(b, _, _, d)| *b == button && d.is_some()) { return; }
+    actions.clear();
+
+    match button {
+        MenuButton::NewRun => {
+            new_run(get_random_seed(), &mut run_rng, &mut player_data);
+            let _ = game_state.set(GameState::MapGenerate);
+        },
+        MenuButton::Continue => {
+            if let Some(saved) = load_saved_run() {
+                continue_run(&saved, &mut run_rng, &mut player_data);
+                pending_floor.0 = saved.floor;
+                let _ = game_state.set(GameState::MapGenerate);
+            }
+        },
+        MenuButton::DailySeed => {
+            new_run(get_daily_seed(), &mut run_rng, &mut player_data);
+            let _ = game_state.set(GameState::MapGenerate);
+        },
+        MenuButton::CustomSeed => seed_entry.0 = Some(String::new()),
+        MenuButton::Codex => { let _ = game_state.set(GameState::Codex); },
+        MenuButton::Achievements => { let _ = game_state.set(GameState::Achievements); },
+        MenuButton::Statistics => { let _ = game_state.set(GameState::Statistics); },
+        MenuButton::Settings => { let _ = game_state.set(GameState::Settings); },
+        MenuButton::Quit => ev_exit.send(AppExit)
+    }
+}
+
+pub fn seed_input(
+    mut actions: ResMut<Actions>,
+    mut seed_entry: ResMut<SeedEntry>,
+    mut ev_chars: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut game_state: ResMut<State<GameState>>,
+    mut run_rng: ResMut<RunRng>,
+    mut player_data: ResMut<PlayerData>,
+    mut prompt_query: Query<&mut Text, With<SeedPrompt>>
+) {
+    let typed: Vec<char> = ev_chars.iter().map(|ev| ev.char).collect();
+    let entry = match seed_entry.0.as_mut() {
+        Some(e) => e,
+        None => return
+    };
+
+    for c in typed {
+        if c.is_ascii_digit() && entry.len() < MAX_SEED_LENGTH { entry.push(c); }
+    }
+    if keys.just_pressed(KeyCode::Back) { entry.pop(); }
+
+    if let Ok(mut text) = prompt_query.get_single_mut() {
+        text.sections[0].value = format!("Seed: {}_", entry);
+    }
+
+    if actions.just_pressed(Action::Confirm) {
+        if let Ok(seed) = entry.parse::<u64>() {
+            actions.clear();
+            new_run(seed, &mut run_rng, &mut player_data);
+            let _ = game_state.set(GameState::MapGenerate);
+        }
+    }
+    if actions.just_pressed(Action::Back) {
+        actions.clear();
+        seed_entry.0 = None;
+        if let Ok(mut text) = prompt_query.get_single_mut() {
+            text.sections[0].value.clear();
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+use bevy::input::mouse::MouseWheel;
+use std::collections::HashSet;
+
+use crate::events::GameEvent;
+use crate::graphics::palette::Palette;
+use crate::items::data::get_item_description;
+use crate::states::GameState;
+use crate::units::UnitKind;
+
+const MAX_MESSAGES: usize = 100;
+const VISIBLE_MESSAGES: usize = 12;
+const PANEL_WIDTH: f32 = 192.;
+
+#[derive(Default)]
+pub struct MessageLog {
+    pub messages: Vec<String>,
+    // how many of the newest messages are scrolled past
+    pub scroll: usize
+}
+
+impl MessageLog {
+    fn push(&mut self, message: String) {
+        self.messages.push(message);
+        if self.messages.len() > MAX_MESSAGES { self.messages.remove(0); }
+        self.scroll = 0;
+    }
+}
+
+#[derive(Component)]
+pub struct MessagePanel;
+
+pub fn collect_messages(
+    mut ev_game: EventReader<GameEvent>,
+    mut log: ResMut<MessageLog>
+) {
+    // kills that are already described by a capture or a block
+    let mut explained = HashSet::new();
+
+    for ev in ev_game.iter() {
+        let message = match *ev {
+            GameEvent::LevelStarted { level } => {
+                if level == 1 { log.messages.clear(); }
+                format!("-- Level {} --", level)
+            },
+            GameEvent::LevelCleared { level } => format!("Level {} cleared", level),
+            GameEvent::UnitCaptured { attacker_kind: UnitKind::Player, victim, victim_kind, .. } => {
+                explained.insert(victim);
+                format!("{:?} captured by you (stole {:?} moves)", victim_kind, victim_kind)
+            },
+            GameEvent::UnitCaptured { attacker_kind, victim, .. } => {
+                explained.insert(victim);
+                format!("{:?} captured you", attacker_kind)
+            },
+            GameEvent::UnitKilled { entity, kind, .. } => {
+                if explained.contains(&entity) { continue; }
+                format!("{} destroyed", unit_name(kind))
+            },
+            GameEvent::ArmorBlocked { attacker, attacker_kind } => {
+                explained.insert(attacker);
+                format!("Armor blocked {} - it's gone", unit_name(attacker_kind))
+            },
+            // already described by the capture
+            GameEvent::PatternStolen { .. } => continue,
+            // every turn has one, the board already shows it
+            GameEvent::UnitMoved { .. } => continue,
+            GameEvent::ItemPicked { kind } => format!("Picked up {:?}", kind),
+            GameEvent::ItemUsed { kind } => format!("{:?}: {}", kind, get_item_description(kind)),
+            GameEvent::BushEntered { kind: UnitKind::Player, paused: true, .. } => {
+                "You step into a bush and lose a turn".to_string()
+            },
+            GameEvent::BushEntered { kind: UnitKind::Player, paused: false, .. } => {
+                "You cut through the bush".to_string()
+            },
+            GameEvent::BushEntered { kind, .. } => {
+                format!("{} steps into a bush and loses a turn", unit_name(kind))
+            },
+            GameEvent::PlayerDied { level } => format!("You died on level {}", level)
+        };
+        log.push(message);
+    }
+}
+
+pub fn scroll_messages(
+    mut ev_wheel: EventReader<MouseWheel>,
+    mut log: ResMut<MessageLog>
+) {
+    for ev in ev_wheel.iter() {
+        let max_scroll = log.messages.len().saturating_sub(VISIBLE_MESSAGES);
+        log.scroll = match ev.y {
+            y if y > 0. => (log.scroll + 1).min(max_scroll),
+            y if y < 0. => log.scroll.saturating_sub(1),
+            _ => log.scroll
+        };
+    }
+}
+
+pub fn draw_messages(
+    mut commands: Commands,
+    log: Res<MessageLog>,
+    game_state: Res<State<GameState>>,
+    panel_query: Query<Entity, With<MessagePanel>>,
+    // inserted once the assets start loading, the system runs before that
+    assets: Option<Res<super::FontAssets>>,
+    palette: Res<Palette>
+) {
+    let visible = matches!(game_state.current(), GameState::PlayerTurn | GameState::NPCTurn);
+    if visible && !log.is_changed() && !panel_query.is_empty() { return; }
+
+    for entity in panel_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !visible { return; }
+    let assets = match assets {
+        Some(a) => a,
+        None => return
+    };
+
+    let end = log.messages.len() - log.scroll.min(log.messages.len());
+    let start = end.saturating_sub(VISIBLE_MESSAGES);
+    let text = log.messages[start..end].join("\n");
+
+    commands.spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(128.),
+                    right: Val::Px(8.),
+                    ..Default::default()
+                },
+                max_size: Size::new(Val::Px(PANEL_WIDTH), Val::Undefined),
+                ..Default::default()
+            },
+            text: Text::from_section(
+                text,
+                TextStyle {
+                    color: palette.status,
+                    font: assets.font.clone(),
+                    font_size: 16.,
+                }
+            ),
+            ..Default::default()
+        })
+        .insert(MessagePanel);
+}
+
+fn unit_name(kind: UnitKind) -> String {
+    match kind {
+        UnitKind::Player => "you".to_string(),
+        _ => format!("{:?}", kind)
+    }
+}
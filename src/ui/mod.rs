@@ -2,11 +2,22 @@ use bevy::prelude::*;
 
 use crate::states::{AnimationState, GameState};
 
+mod achievements;
+mod codex;
 pub mod cursor;
-mod input;
+pub mod input;
+mod intents;
+mod interlude;
+mod keyboard;
+mod message_log;
 mod main_menu;
+mod pause_menu;
 mod player_menu;
+mod settings_menu;
+mod statistics;
 mod status;
+mod timeline;
+mod tooltip;
 
 pub struct RedrawUIEvent;
 
@@ -16,8 +27,11 @@ impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(
             SystemSet::on_update(GameState::MainMenu)
-                .with_system(input::mouse_press_menu)
+                .with_system(main_menu::menu_navigation)
+                .with_system(main_menu::seed_input)
         );
+        app.init_resource::<main_menu::MenuFocus>();
+        app.init_resource::<main_menu::SeedEntry>();
         app.add_system_set(
             SystemSet::on_enter(GameState::MainMenu)
                 .with_system(main_menu::draw_menu)
@@ -26,12 +40,95 @@ impl Plugin for UIPlugin {
             SystemSet::on_exit(GameState::MainMenu)
                 .with_system(main_menu::clear_menu)
         );
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Achievements)
+                .with_system(achievements::draw_achievements)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Achievements)
+                .with_system(input::mouse_press_game_over)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Achievements)
+                .with_system(achievements::clear_achievements)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::NPCTurn)
+                .with_system(pause_menu::open_pause)
+        );
+        app.init_resource::<pause_menu::PauseFocus>();
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Paused)
+                .with_system(pause_menu::draw_pause)
+        );
+        app.add_system_set(
+            SystemSet::on_resume(GameState::Paused)
+                .with_system(pause_menu::draw_pause)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Paused)
+                .with_system(pause_menu::pause_input)
+        );
+        // the settings screen is pushed over the pause menu
+        app.add_system_set(
+            SystemSet::on_pause(GameState::Paused)
+                .with_system(pause_menu::clear_pause)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Paused)
+                .with_system(pause_menu::clear_pause)
+        );
+        // a restarted floor goes straight back to map generation
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MapGenerate)
+                .with_system(cursor::clear_cursor)
+                .with_system(status::clear_status)
+                .with_system(player_menu::clear_menu)
+        );
+        app.init_resource::<settings_menu::SettingsFocus>();
+        app.add_system_set(
+            SystemSet::on_update(GameState::Settings)
+                .with_system(settings_menu::draw_settings)
+                .with_system(settings_menu::settings_input)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Settings)
+                .with_system(settings_menu::clear_settings)
+        );
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Statistics)
+                .with_system(statistics::draw_statistics)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Statistics)
+                .with_system(input::mouse_press_game_over)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Statistics)
+                .with_system(statistics::clear_statistics)
+        );
+        app.init_resource::<codex::CodexPage>();
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Codex)
+                .with_system(codex::reset_codex)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Codex)
+                .with_system(codex::codex_input)
+                .with_system(codex::draw_codex)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Codex)
+                .with_system(codex::clear_codex)
+        );
+        app.add_system(achievements::show_toasts);
+        app.add_system(achievements::update_toasts);
         app.add_system_set(
             SystemSet::on_update(GameState::GameOver)
                 .with_system(input::mouse_press_game_over)
         );
         app.add_system_set(
-            SystemSet::on_exit(GameState::GameOver)
+            SystemSet::on_enter(GameState::MainMenu)
                 .with_system(cursor::clear_cursor)
                 .with_system(status::clear_status)
                 .with_system(player_menu::clear_menu)
@@ -43,6 +140,18 @@ impl Plugin for UIPlugin {
                 .with_system(load_assets)
         );
         app.add_event::<RedrawUIEvent>();
+        app.init_resource::<cursor::DangerMap>();
+        app.add_system(cursor::apply_palette);
+        app.init_resource::<keyboard::BoardCursor>();
+        app.init_resource::<keyboard::InterludeFocus>();
+        app.init_resource::<timeline::Timeline>();
+        app.init_resource::<tooltip::Tooltip>();
+        app.add_system(timeline::draw_timeline);
+        app.add_system(timeline::hover_timeline);
+        app.init_resource::<message_log::MessageLog>();
+        app.add_system(message_log::collect_messages);
+        app.add_system(message_log::scroll_messages);
+        app.add_system(message_log::draw_messages.after(message_log::collect_messages));
         app.add_event::<player_menu::PlayerButtonClickEvent>();
         app.add_system_set(
             SystemSet::on_enter(GameState::PlayerTurn)
@@ -51,14 +160,43 @@ impl Plugin for UIPlugin {
         app.add_system_set(
             SystemSet::on_update(GameState::PlayerTurn)
                 .with_system(cursor::draw_cursor)
+                .with_system(intents::draw_intents)
                 .with_system(input::mouse_press_game)
+                .with_system(input::toggle_danger_map)
+                .with_system(keyboard::keyboard_game)
+                .with_system(keyboard::draw_board_cursor)
+                .with_system(tooltip::draw_tooltip)
+                .with_system(pause_menu::open_pause.after(keyboard::keyboard_game))
                 .with_system(status::draw_status)
                 .with_system(player_menu::draw_menu)
                 .with_system(player_menu::button_click)
         );
+        app.add_system_set(
+            SystemSet::on_enter(GameState::Interlude)
+                .with_system(cursor::clear_cursor)
+                .with_system(status::clear_status)
+                .with_system(player_menu::clear_menu)
+        );
+        app.add_system_set(
+            SystemSet::on_update(GameState::Interlude)
+                .with_system(interlude::draw_interlude)
+                .with_system(interlude::interlude_click)
+                .with_system(keyboard::keyboard_interlude)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::Interlude)
+                .with_system(interlude::clear_interlude)
+        );
+        app.add_system_set(
+            SystemSet::on_exit(GameState::PlayerTurn)
+                .with_system(intents::clear_intents)
+                .with_system(keyboard::clear_board_cursor)
+                .with_system(tooltip::clear_tooltip)
+        );
         app.add_system_set(
             SystemSet::on_enter(AnimationState::Animating)
                 .with_system(cursor::clear_cursor)
+                .with_system(intents::clear_intents)
         );
     }
 }
@@ -79,5 +217,5 @@ pub fn load_assets(
 }
 
 pub struct FontAssets {
-    font: Handle<Font>
+    pub font: Handle<Font>
 }
\ No newline at end of file
@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+
+use crate::actions::{Action, Actions, Binding};
+use crate::graphics::palette::Palette;
+use crate::run::{RunRng, continue_run, load_saved_run, remove_saved_run, save_floor};
+use crate::states::{FadeState, GameState};
+use crate::undo::UndoStack;
+use crate::units::player::PlayerData;
+
+use super::player_menu::PlayerMenuAssets;
+
+const BACKGROUND_COLOR: Color = Color::Rgba { red: 0., green: 0., blue: 0., alpha: 0.6 };
+const DISABLED_COLOR: Color = Color::Rgba { red: 0.4, green: 0.4, blue: 0.4, alpha: 1. };
+
+#[derive(Clone, Copy, Component, PartialEq, Eq)]
+pub enum PauseButton {
+    Resume,
+    // back to the checkpoint saved when the floor started
+    RestartFloor,
+    Settings,
+    // saves the floor as it was at the last move
+    SaveAndQuit,
+    AbandonRun
+}
+
+const PAUSE_BUTTONS: [PauseButton; 5] = [
+    PauseButton::Resume,
+    PauseButton::RestartFloor,
+    PauseButton::Settings,
+    PauseButton::SaveAndQuit,
+    PauseButton::AbandonRun
+];
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+// index of the focused button in PAUSE_BUTTONS
+#[derive(Default)]
+pub struct PauseFocus(pub usize);
+
+fn get_button_label(button: PauseButton) -> &'static str {
+    match button {
+        PauseButton::Resume => "Resume",
+        PauseButton::RestartFloor => "Restart floor",
+        PauseButton::Settings => "Settings",
+        PauseButton::SaveAndQuit => "Save & Quit",
+        PauseButton::AbandonRun => "Abandon run"
+    }
+}
+
+pub fn open_pause(
+    mut actions: ResMut<Actions>,
+    mut game_state: ResMut<State<GameState>>,
+    mut focus: ResMut<PauseFocus>,
+    fade_state: Res<State<FadeState>>
+) {
+    // the fade finishes a level transition, it can't be frozen halfway
+    if fade_state.current() != &FadeState::Hidden { return; }
+    if !actions.just_pressed(Action::Pause) { return; }
+    actions.clear();
+    focus.0 = 0;
+    let _ = game_state.push(GameState::Paused);
+}
+
+pub fn clear_pause(
+    mut commands: Commands,
+    query: Query<Entity, With<PauseMenu>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity)
+            .despawn_recursive()
+    }
+}
+
+pub fn draw_pause(
+    mut commands: Commands,
+    font_assets: Res<super::FontAssets>,
+    menu_assets: Res<PlayerMenuAssets>,
+    palette: Res<Palette>
+) {
+    let has_saved_run = load_saved_run().is_some();
+
+    commands.spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: BACKGROUND_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(PauseMenu)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle::from_section(
+                "Paused",
+                TextStyle {
+                    color: palette.status,
+                    font: font_assets.font.clone(),
+                    font_size: 32.
+                }
+            ));
+            for button in PAUSE_BUTTONS {
+                let disabled = button == PauseButton::RestartFloor && !has_saved_run;
+                parent.spawn_bundle(ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(8.)),
+                            margin: UiRect::all(Val::Px(8.)),
+                            ..Default::default()
+                        },
+                        color: palette.button.into(),
+                        image: UiImage(menu_assets.button_image.clone()),
+                        ..Default::default()
+                    })
+                    .insert(button)
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle::from_section(
+                            get_button_label(button),
+                            TextStyle {
+                                color: match disabled {
+                                    true => DISABLED_COLOR,
+                                    false => palette.button_text
+                                },
+                                font: font_assets.font.clone(),
+                                font_size: 24.
+                            }
+                        ));
+                    });
+            }
+        });
+}
+
+pub fn pause_input(
+    mut actions: ResMut<Actions>,
+    mut focus: ResMut<PauseFocus>,
+    mut game_state: ResMut<State<GameState>>,
+    mut run_rng: ResMut<RunRng>,
+    mut player_data: ResMut<PlayerData>,
+    undo_stack: Res<UndoStack>,
+    mut button_query: Query<(&PauseButton, &Interaction, &mut UiColor)>,
+    palette: Res<Palette>
+) {
+    let mut activated = None;
+    for (button, interaction, _) in button_query.iter() {
+        let idx = PAUSE_BUTTONS.iter().position(|b| b == button).unwrap_or(0);
+        match *interaction {
+            Interaction::Hovered => focus.0 = idx,
+            Interaction::Clicked if actions.just_pressed(Action::Move) => activated = Some(*button),
+            _ => ()
+        }
+    }
+    for (action, binding) in actions.iter_just_pressed() {
+        match action {
+            Action::Up => focus.0 = (focus.0 + PAUSE_BUTTONS.len() - 1) % PAUSE_BUTTONS.len(),
+            Action::Down => focus.0 = (focus.0 + 1) % PAUSE_BUTTONS.len(),
+            Action::Confirm => activated = Some(PAUSE_BUTTONS[focus.0]),
+            Action::Move if !matches!(binding, Binding::Mouse(_)) => activated = Some(PAUSE_BUTTONS[focus.0]),
+            Action::Pause | Action::Back => activated = Some(PauseButton::Resume),
+            _ => ()
+        }
+    }
+
+    for (button, _, mut color) in button_query.iter_mut() {
+        *color = match PAUSE_BUTTONS[focus.0] == *button {
+            true => palette.button_click.into(),
+            false => palette.button.into()
+        };
+    }
+
+    let button = match activated {
+        Some(b) => b,
+        None => return
+    };
+    actions.clear();
+
+    // replace unwinds the frozen turn state below, so its exit systems run
+    match button {
+        PauseButton::Resume => { let _ = game_state.pop(); },
+        PauseButton::RestartFloor => {
+            if let Some(saved) = load_saved_run() {
+                continue_run(&saved, &mut run_rng, &mut player_data);
+                let _ = game_state.replace(GameState::MapGenerate);
+            }
+        },
+        PauseButton::Settings => { let _ = game_state.push(GameState::Settings); },
+        PauseButton::SaveAndQuit => {
+            // the last state the player could act from, an npc turn in progress is not saved
+            if let Some(floor) = undo_stack.0.last() {
+                save_floor(floor.clone());
+            }
+            let _ = game_state.replace(GameState::MainMenu);
+        },
+        PauseButton::AbandonRun => {
+            remove_saved_run();
+            let _ = game_state.replace(GameState::MainMenu);
+        }
+    }
+}
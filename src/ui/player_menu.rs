@@ -5,24 +5,38 @@ const BUTTON_WIDTH: Val = Val::Px(64.);
 const BUTTON_HEIGHT: Val = Val::Px(96.);
 const BUTTON_MARGIN: Val = Val::Px(16.);
 const MENU_PADDING: Val = Val::Px(16.);
+const PROMPT_BOTTOM: Val = Val::Px(144.);
+// the move pattern grid on a hand card
+const PATTERN_SIZE: f32 = 48.;
 
-pub const BUTTON_COLOR: Color = Color::WHITE;
-pub const STATUS_COLOR: Color = Color::Rgba { red: 0.77, green: 0.67, blue: 0.58, alpha: 1. };
-pub const BUTTON_COLOR_CLICK: Color = Color::GRAY;
-pub const BUTTON_TEXT_COLOR: Color = Color::Rgba{ red: 0.27, green: 0.22, blue: 0.19, alpha: 1. };
 pub const TRANSPARENT: Color = Color::Rgba{ red: 0., green: 0., blue: 0., alpha: 0. };
 
-use crate::items::{UseItemEvent, ItemKind};
+use std::collections::HashMap;
+
+use crate::graphics::palette::Palette;
+use crate::items::{
+    InventoryEvent, InventoryPrompt, Item, UseItemEvent, ItemKind, Relic,
+    data::{get_item_label, get_item_relic, get_item_types}
+};
 use crate::units::{
     Unit,
-    player::{Player, PlayerData}
+    player::{MoveCard, Player, PlayerData, SelectCardEvent}
 };
+use crate::vectors::Vector2Int;
 
-pub struct PlayerButtonClickEvent(pub Entity);
+pub enum ButtonAction {
+    Use,
+    Discard,
+    Move
+}
+
+pub struct PlayerButtonClickEvent(pub Entity, pub ButtonAction);
 pub struct PlayerMenuAssets {
     pub button_image: Handle<Image>,
     pub boot_image: Handle<Image>,
-    pub shield_image: Handle<Image>
+    pub shield_image: Handle<Image>,
+    pub item_images: HashMap<ItemKind, Handle<Image>>,
+    pub relic_images: HashMap<Relic, Handle<Image>>
 }
 
 #[derive(Component)]
@@ -52,7 +66,10 @@ pub fn draw_menu(
     player_query: Query<&Unit, With<Player>>,
     font_assets: Res<super::FontAssets>,
     assets: Res<PlayerMenuAssets>,
+    prompt: Res<InventoryPrompt>,
+    item_query: Query<&Item>,
     mut ev_redraw_ui: EventReader<super::RedrawUIEvent>,
+    palette: Res<Palette>
 ) {
     for _ in ev_redraw_ui.iter() {
         destroy_menu(&mut commands, &menu_query);
@@ -73,17 +90,184 @@ pub fn draw_menu(
             .insert(PlayerMenu)
             .id();
 
-            draw_card_menu(&mut commands, &player_data, &assets, &font_assets, menu);
-            draw_status_menu(&mut commands, &player_data, &player_query, &assets, menu);
+            draw_card_menu(&mut commands, &player_data, &assets, &font_assets, menu, &palette);
+            draw_status_menu(&mut commands, &player_data, &player_query, &assets, menu, &palette);
+            draw_hand_menu(&mut commands, &player_data, &assets, &font_assets, &palette);
+
+            if let Some(item) = prompt.item.and_then(|e| item_query.get(e).ok()) {
+                draw_prompt(&mut commands, item, &assets, &font_assets, &palette);
+            }
     }
 }
 
+fn draw_prompt(
+    commands: &mut Commands,
+    item: &Item,
+    assets: &Res<PlayerMenuAssets>,
+    font_assets: &Res<super::FontAssets>,
+    palette: &Palette
+) {
+    let text_style = TextStyle {
+        color: palette.status,
+        font: font_assets.font.clone(),
+        font_size: 24.,
+    };
+    commands.spawn_bundle(NodeBundle{
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: PROMPT_BOTTOM,
+                    left: MENU_PADDING,
+                    ..Default::default()
+                },
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color: TRANSPARENT.into(),
+            ..default()
+        })
+        .insert(PlayerMenu)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle::from_section(
+                format!("Inventory full: click a card to swap it for {:?}", item.kind),
+                text_style.clone()
+            ).with_style(Style {
+                margin: UiRect::new(Val::Px(0.), BUTTON_MARGIN, Val::Px(0.), Val::Px(0.)),
+                ..Default::default()
+            }));
+            parent.spawn_bundle(ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(8.)),
+                    ..Default::default()
+                },
+                color: palette.button.into(),
+                image: UiImage(assets.button_image.clone()),
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle::from_section(
+                    "Leave",
+                    TextStyle { color: palette.button_text, ..text_style.clone() }
+                ));
+            })
+            .insert(LeaveButton);
+        });
+}
+
+fn draw_hand_menu(
+    commands: &mut Commands,
+    player_data: &Res<PlayerData>,
+    assets: &Res<PlayerMenuAssets>,
+    font_assets: &Res<super::FontAssets>,
+    palette: &Palette
+) {
+    if player_data.hand.is_empty() { return; }
+
+    commands.spawn_bundle(NodeBundle{
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: MENU_PADDING,
+                    left: MENU_PADDING,
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.), BUTTON_HEIGHT),
+                ..Default::default()
+            },
+            color: TRANSPARENT.into(),
+            ..default()
+        })
+        .insert(PlayerMenu)
+        .with_children(|parent| {
+            for (idx, card) in player_data.hand.iter().enumerate() {
+                let text_color = match player_data.selected_card {
+                    Some(selected) if selected == idx => palette.status,
+                    _ => palette.button_text
+                };
+                parent.spawn_bundle(
+                    get_button_bundle(assets, palette)
+                )
+                .with_children(|parent| {
+                    draw_card_pattern(parent, card, text_color);
+                    parent.spawn_bundle(TextBundle::from_section(
+                        format!("x{}", card.uses),
+                        TextStyle {
+                            color: text_color,
+                            font: font_assets.font.clone(),
+                            font_size: 24.,
+                        }
+                    ));
+                })
+                .insert(
+                    HandButton{idx}
+                );
+            }
+        });
+}
+
+// the player sits in the middle, dimmed
+fn draw_card_pattern(
+    parent: &mut ChildBuilder,
+    card: &MoveCard,
+    color: Color
+) {
+    let range = card.behaviour.pattern.iter()
+        .map(|v| v.x.abs().max(v.y.abs()))
+        .max()
+        .unwrap_or(0);
+    let side = 2 * range + 1;
+    let cell = (PATTERN_SIZE / side as f32).floor();
+    let mut origin_color = color;
+    origin_color.set_a(0.35);
+
+    parent.spawn_bundle(NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            margin: UiRect::new(Val::Px(0.), Val::Px(0.), Val::Px(0.), Val::Px(8.)),
+            ..Default::default()
+        },
+        color: TRANSPARENT.into(),
+        focus_policy: FocusPolicy::Pass,
+        ..Default::default()
+    })
+    .with_children(|parent| {
+        // top row first
+        for y in (-range..=range).rev() {
+            parent.spawn_bundle(NodeBundle {
+                color: TRANSPARENT.into(),
+                focus_policy: FocusPolicy::Pass,
+                ..Default::default()
+            })
+            .with_children(|parent| {
+                for x in -range..=range {
+                    let cell_color = match (x, y) {
+                        (0, 0) => origin_color,
+                        _ if card.behaviour.pattern.contains(&Vector2Int::new(x, y)) => color,
+                        _ => TRANSPARENT
+                    };
+                    parent.spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(cell - 1.), Val::Px(cell - 1.)),
+                            margin: UiRect::new(Val::Px(0.), Val::Px(1.), Val::Px(0.), Val::Px(1.)),
+                            ..Default::default()
+                        },
+                        color: cell_color.into(),
+                        focus_policy: FocusPolicy::Pass,
+                        ..Default::default()
+                    });
+                }
+            });
+        }
+    });
+}
+
 fn draw_card_menu(
     commands: &mut Commands,
     player_data: &Res<PlayerData>,
     assets: &Res<PlayerMenuAssets>,
     font_assets: &Res<super::FontAssets>,
-    menu: Entity
+    menu: Entity,
+    palette: &Palette
 ) {
     commands.entity(menu)
         .with_children(|parent| {
@@ -98,7 +282,7 @@ fn draw_card_menu(
                 .with_children(|parent| {
                     for (idx, item) in player_data.items.iter().enumerate() {
                         parent.spawn_bundle(
-                            get_button_bundle(assets)
+                            get_button_bundle(assets, palette)
                         )
                         .with_children(|parent| {
                             parent.spawn_bundle(ImageBundle {
@@ -106,19 +290,15 @@ fn draw_card_menu(
                                     size: Size::new(Val::Px(64.), Val::Px(64.)),
                                     ..Default::default()
                                 },
-                                color: BUTTON_TEXT_COLOR.into(),
-                                image: UiImage(assets.boot_image.clone()),
+                                color: palette.button_text.into(),
+                                image: UiImage(assets.item_images[&item.kind].clone()),
                                 focus_policy: FocusPolicy::Pass,
                                 ..Default::default()
                             });
                             parent.spawn_bundle(TextBundle::from_section(
-                                match item.kind {
-                                    ItemKind::SpeedMushroom => "+1",
-                                    ItemKind::StopMushroom => "0",
-                                    _ => ""
-                                },
+                                get_item_label(item.kind),
                                 TextStyle {
-                                    color: BUTTON_TEXT_COLOR,
+                                    color: palette.button_text,
                                     font: font_assets.font.clone(),
                                     font_size: 32.,
                                     ..Default::default()
@@ -138,7 +318,8 @@ fn draw_status_menu(
     player_data: &Res<PlayerData>,
     player_query: &Query<&Unit, With<Player>>,
     assets: &Res<PlayerMenuAssets>,
-    menu: Entity
+    menu: Entity,
+    palette: &Palette
 ) {
     commands.entity(menu)
         .with_children(|parent| {
@@ -162,7 +343,7 @@ fn draw_status_menu(
                                     margin: UiRect::new(Val::Px(0.), BUTTON_MARGIN, Val::Px(0.), Val::Px(0.)),
                                     ..Default::default()
                                 },
-                                color: STATUS_COLOR.into(),
+                                color: palette.status.into(),
                                 image: UiImage(assets.boot_image.clone()),
                                 focus_policy: FocusPolicy::Pass,
                                 ..Default::default()
@@ -177,12 +358,26 @@ fn draw_status_menu(
                                 margin: UiRect::new(Val::Px(0.), BUTTON_MARGIN, Val::Px(0.), Val::Px(0.)),
                                 ..Default::default()
                             },
-                            color: STATUS_COLOR.into(),
+                            color: palette.status.into(),
                             image: UiImage(assets.shield_image.clone()),
                             focus_policy: FocusPolicy::Pass,
                             ..Default::default()
                         });
                     }
+                    // draw relics
+                    for relic in player_data.relics.iter() {
+                        parent.spawn_bundle(ImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(64.), Val::Px(64.)),
+                                margin: UiRect::new(Val::Px(0.), BUTTON_MARGIN, Val::Px(0.), Val::Px(0.)),
+                                ..Default::default()
+                            },
+                            color: palette.status.into(),
+                            image: UiImage(assets.relic_images[relic].clone()),
+                            focus_policy: FocusPolicy::Pass,
+                            ..Default::default()
+                        });
+                    }
                 });
         });
 }
@@ -190,17 +385,38 @@ fn draw_status_menu(
 pub fn button_click(
     mut ev_button_click: EventReader<PlayerButtonClickEvent>,
     mut ev_ui: EventWriter<UseItemEvent>,
-    button_query: Query<&PlayerButton>
+    mut ev_select: EventWriter<SelectCardEvent>,
+    mut ev_inventory: EventWriter<InventoryEvent>,
+    prompt: Res<InventoryPrompt>,
+    button_query: Query<&PlayerButton>,
+    hand_query: Query<&HandButton>,
+    leave_query: Query<&LeaveButton>
 ) {
     for ev in ev_button_click.iter() {
         if let Ok(button) = button_query.get(ev.0) {
-            ev_ui.send(UseItemEvent(button.idx));
+            match ev.1 {
+                ButtonAction::Use if prompt.item.is_some() => {
+                    ev_inventory.send(InventoryEvent::Swap(button.idx))
+                },
+                ButtonAction::Use => ev_ui.send(UseItemEvent(button.idx)),
+                ButtonAction::Discard => ev_inventory.send(InventoryEvent::Discard(button.idx)),
+                ButtonAction::Move => ev_inventory.send(InventoryEvent::MoveLeft(button.idx))
+            }
+        }
+        if let Ok(button) = hand_query.get(ev.0) {
+            if let ButtonAction::Use = ev.1 {
+                ev_select.send(SelectCardEvent(button.idx));
+            }
+        }
+        if leave_query.get(ev.0).is_ok() {
+            ev_inventory.send(InventoryEvent::Leave);
         }
     }
 }
 
 fn get_button_bundle(
     assets: &Res<PlayerMenuAssets>,
+    palette: &Palette
 ) -> ButtonBundle {
     ButtonBundle{
         style: Style {
@@ -211,7 +427,7 @@ fn get_button_bundle(
             flex_direction: FlexDirection::ColumnReverse,
             ..Default::default()
         },
-        color: BUTTON_COLOR.into(),
+        color: palette.button.into(),
         image: UiImage(assets.button_image.clone()),
         ..Default::default()
     }
@@ -222,13 +438,22 @@ pub struct PlayerButton {
     pub idx: usize
 }
 
+#[derive(Component)]
+pub struct HandButton {
+    pub idx: usize
+}
+
+#[derive(Component)]
+pub struct LeaveButton;
+
 pub fn load_assets(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut asset_list: ResMut<crate::assets::AssetList> 
 ) {
-    let button_handle = asset_server.load("card.png");
-    asset_list.0.push(button_handle.clone_untyped());
+    // buttons reuse the card frame
+    let card_handle = asset_server.load("card.png");
+    asset_list.0.push(card_handle.clone_untyped());
 
 
     let boot_handle = asset_server.load("boot.png");
@@ -237,11 +462,55 @@ pub fn load_assets(
     let shield_handle = asset_server.load("shield.png");
     asset_list.0.push(shield_handle.clone_untyped());
 
+    let mut item_images = HashMap::new();
+    let mut relic_images = HashMap::new();
+    for kind in get_item_types() {
+        let handle = match get_item_icon(kind) {
+            "boot.png" => boot_handle.clone(),
+            "shield.png" => shield_handle.clone(),
+            path => {
+                let handle = asset_server.load(path);
+                asset_list.0.push(handle.clone_untyped());
+                handle
+            }
+        };
+        if let Some(relic) = get_item_relic(kind) {
+            relic_images.insert(relic, handle.clone());
+        }
+        item_images.insert(kind, handle);
+    }
+
     commands.insert_resource(
         PlayerMenuAssets {
-            button_image: button_handle,
+            button_image: card_handle,
             boot_image: boot_handle,
-            shield_image: shield_handle
+            shield_image: shield_handle,
+            item_images,
+            relic_images
         }
     );
-}
\ No newline at end of file
+}
+fn get_item_icon(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::SpeedMushroom => "boot.png",
+        ItemKind::StopMushroom => "mushroom.png",
+        ItemKind::Armor => "shield.png",
+        ItemKind::Teleport => "teleport.png",
+        ItemKind::Swap => "swap.png",
+        ItemKind::Freeze => "freeze.png",
+        ItemKind::Frost => "frost.png",
+        ItemKind::Bomb => "bomb.png",
+        ItemKind::Eye => "eye.png",
+        ItemKind::Ghost => "ghost.png",
+        ItemKind::Wings => "wings.png",
+        ItemKind::Spring => "spring.png",
+        ItemKind::Crown => "crown.png",
+        ItemKind::Machete => "machete.png",
+        ItemKind::Backpack => "backpack.png",
+        ItemKind::Coffee => "coffee.png",
+        ItemKind::Snail => "snail.png",
+        ItemKind::Vine => "vine.png",
+        ItemKind::Venom => "venom.png",
+        ItemKind::Mirror => "mirror.png"
+    }
+}
@@ -0,0 +1,236 @@
+use bevy::prelude::*;
+
+use crate::actions::{Action, Actions, Binding};
+use crate::graphics::palette::{Palette, get_palette_kinds, get_palette_name};
+use crate::settings::{
+    Settings,
+    get_animation_speeds,
+    get_fast_forward_speeds,
+    get_language_name,
+    get_languages,
+    get_pattern_mode_name,
+    get_pattern_modes,
+    get_window_sizes
+};
+use crate::states::GameState;
+
+use super::player_menu::PlayerMenuAssets;
+
+const BACKGROUND_COLOR: Color = Color::Rgba { red: 0.11, green: 0.09, blue: 0.08, alpha: 1. };
+
+#[derive(Clone, Copy, Component, PartialEq, Eq)]
+pub enum SettingsButton {
+    WindowSize,
+    Fullscreen,
+    AnimationSpeed,
+    FastForwardSpeed,
+    InstantMoves,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Palette,
+    PatternMode,
+    Language,
+    Back
+}
+
+const SETTINGS_BUTTONS: [SettingsButton; 12] = [
+    SettingsButton::WindowSize,
+    SettingsButton::Fullscreen,
+    SettingsButton::AnimationSpeed,
+    SettingsButton::FastForwardSpeed,
+    SettingsButton::InstantMoves,
+    SettingsButton::MasterVolume,
+    SettingsButton::MusicVolume,
+    SettingsButton::SfxVolume,
+    SettingsButton::Palette,
+    SettingsButton::PatternMode,
+    SettingsButton::Language,
+    SettingsButton::Back
+];
+
+// volumes are changed in tenths
+const VOLUME_STEPS: f32 = 10.;
+
+#[derive(Component)]
+pub struct SettingsMenu;
+
+// index of the focused button in SETTINGS_BUTTONS
+#[derive(Default)]
+pub struct SettingsFocus(pub usize);
+
+fn get_button_label(button: SettingsButton, settings: &Settings) -> String {
+    match button {
+        SettingsButton::WindowSize => format!("Window: {}x{}", settings.window_size.0, settings.window_size.1),
+        SettingsButton::Fullscreen => format!("Fullscreen: {}", if settings.fullscreen { "On" } else { "Off" }),
+        SettingsButton::AnimationSpeed => format!("Animation speed: {}x", settings.animation_speed),
+        SettingsButton::FastForwardSpeed => format!("Fast-forward: {}x", settings.fast_forward_speed),
+        SettingsButton::InstantMoves => format!("Instant enemy moves: {}", if settings.instant_npc_moves { "On" } else { "Off" }),
+        SettingsButton::MasterVolume => format!("Master volume: {:.0}%", settings.master_volume * 100.),
+        SettingsButton::MusicVolume => format!("Music volume: {:.0}%", settings.music_volume * 100.),
+        SettingsButton::SfxVolume => format!("Sound volume: {:.0}%", settings.sfx_volume * 100.),
+        SettingsButton::Palette => format!("Colors: {}", get_palette_name(settings.palette)),
+        SettingsButton::PatternMode => format!("Captures: {}", get_pattern_mode_name(settings.pattern_mode)),
+        SettingsButton::Language => format!("Language: {}", get_language_name(settings.language)),
+        SettingsButton::Back => "Back".to_string()
+    }
+}
+
+// steps the setting behind the button forwards or backwards
+fn change_setting(button: SettingsButton, settings: &mut Settings, step: i32) {
+    match button {
+        SettingsButton::WindowSize => settings.window_size = cycle(&get_window_sizes(), settings.window_size, step),
+        SettingsButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+        SettingsButton::AnimationSpeed => settings.animation_speed = cycle(&get_animation_speeds(), settings.animation_speed, step),
+        SettingsButton::FastForwardSpeed => settings.fast_forward_speed = cycle(&get_fast_forward_speeds(), settings.fast_forward_speed, step),
+        SettingsButton::InstantMoves => settings.instant_npc_moves = !settings.instant_npc_moves,
+        SettingsButton::MasterVolume => settings.master_volume = step_volume(settings.master_volume, step),
+        SettingsButton::MusicVolume => settings.music_volume = step_volume(settings.music_volume, step),
+        SettingsButton::SfxVolume => settings.sfx_volume = step_volume(settings.sfx_volume, step),
+        SettingsButton::Palette => settings.palette = cycle(&get_palette_kinds(), settings.palette, step),
+        SettingsButton::PatternMode => settings.pattern_mode = cycle(&get_pattern_modes(), settings.pattern_mode, step),
+        SettingsButton::Language => settings.language = cycle(&get_languages(), settings.language, step),
+        SettingsButton::Back => ()
+    }
+}
+
+// volumes stop at the ends instead of wrapping around
+fn step_volume(volume: f32, step: i32) -> f32 {
+    ((volume * VOLUME_STEPS).round() + step as f32).clamp(0., VOLUME_STEPS) / VOLUME_STEPS
+}
+
+fn cycle<T: Copy + PartialEq>(values: &[T], current: T, step: i32) -> T {
+    let idx = values.iter().position(|v| *v == current).unwrap_or(0) as i32;
+    values[(idx + step).rem_euclid(values.len() as i32) as usize]
+}
+
+pub fn clear_settings(
+    mut commands: Commands,
+    query: Query<Entity, With<SettingsMenu>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity)
+            .despawn_recursive()
+    }
+}
+
+// drawn again whenever a setting changes
+pub fn draw_settings(
+    mut commands: Commands,
+    query: Query<Entity, With<SettingsMenu>>,
+    settings: Res<Settings>,
+    font_assets: Res<super::FontAssets>,
+    menu_assets: Res<PlayerMenuAssets>,
+    palette: Res<Palette>
+) {
+    if !query.is_empty() && !settings.is_changed() && !palette.is_changed() { return; }
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    commands.spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: BACKGROUND_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(SettingsMenu)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle::from_section(
+                "Settings",
+                TextStyle {
+                    color: palette.status,
+                    font: font_assets.font.clone(),
+                    font_size: 32.
+                }
+            ));
+            for button in SETTINGS_BUTTONS {
+                parent.spawn_bundle(ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(6.)),
+                            margin: UiRect::all(Val::Px(4.)),
+                            ..Default::default()
+                        },
+                        color: palette.button.into(),
+                        image: UiImage(menu_assets.button_image.clone()),
+                        ..Default::default()
+                    })
+                    .insert(button)
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle::from_section(
+                            get_button_label(button, &settings),
+                            TextStyle {
+                                color: palette.button_text,
+                                font: font_assets.font.clone(),
+                                font_size: 24.
+                            }
+                        ));
+                    });
+            }
+            parent.spawn_bundle(TextBundle::from_section(
+                "Left and right to change, Esc to go back",
+                TextStyle {
+                    color: palette.status,
+                    font: font_assets.font.clone(),
+                    font_size: 16.
+                }
+            ));
+        });
+}
+
+pub fn settings_input(
+    mut actions: ResMut<Actions>,
+    mut focus: ResMut<SettingsFocus>,
+    mut settings: ResMut<Settings>,
+    mut game_state: ResMut<State<GameState>>,
+    mut button_query: Query<(&SettingsButton, &Interaction, &mut UiColor)>,
+    palette: Res<Palette>
+) {
+    // (button, step) - the step is the direction the setting is changed in
+    let mut activated = None;
+    for (button, interaction, _) in button_query.iter() {
+        let idx = SETTINGS_BUTTONS.iter().position(|b| b == button).unwrap_or(0);
+        match *interaction {
+            Interaction::Hovered => focus.0 = idx,
+            Interaction::Clicked if actions.just_pressed(Action::Move) => activated = Some((*button, 1)),
+            _ => ()
+        }
+    }
+    let focused = SETTINGS_BUTTONS[focus.0];
+    for (action, binding) in actions.iter_just_pressed() {
+        match action {
+            Action::Up => focus.0 = (focus.0 + SETTINGS_BUTTONS.len() - 1) % SETTINGS_BUTTONS.len(),
+            Action::Down => focus.0 = (focus.0 + 1) % SETTINGS_BUTTONS.len(),
+            Action::Left => activated = Some((focused, -1)),
+            Action::Right | Action::Confirm => activated = Some((focused, 1)),
+            Action::Move if !matches!(binding, Binding::Mouse(_)) => activated = Some((focused, 1)),
+            Action::Back => activated = Some((SettingsButton::Back, 0)),
+            _ => ()
+        }
+    }
+
+    for (button, _, mut color) in button_query.iter_mut() {
+        *color = match SETTINGS_BUTTONS[focus.0] == *button {
+            true => palette.button_click.into(),
+            false => palette.button.into()
+        };
+    }
+
+    match activated {
+        Some((SettingsButton::Back, _)) => {
+            actions.clear();
+            // opened from the pause menu, or from the main menu
+            match game_state.inactives().is_empty() {
+                true => { let _ = game_state.set(GameState::MainMenu); },
+                false => { let _ = game_state.pop(); }
+            }
+        },
+        Some((button, step)) => change_setting(button, &mut settings, step),
+        None => ()
+    }
+}
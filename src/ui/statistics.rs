@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::graphics::palette::Palette;
+use crate::profile::Profile;
+use crate::statistics::get_statistic_rows;
+
+const BACKGROUND_COLOR: Color = Color::Rgba { red: 0.11, green: 0.09, blue: 0.08, alpha: 1. };
+
+#[derive(Component)]
+pub struct StatisticsList;
+
+pub fn clear_statistics(
+    mut commands: Commands,
+    query: Query<Entity, With<StatisticsList>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity)
+            .despawn_recursive()
+    }
+}
+
+pub fn draw_statistics(
+    mut commands: Commands,
+    profile: Res<Profile>,
+    font_assets: Res<super::FontAssets>,
+    palette: Res<Palette>
+) {
+    let text_style = TextStyle {
+        color: palette.status,
+        font: font_assets.font.clone(),
+        font_size: 32.,
+    };
+
+    commands.spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: BACKGROUND_COLOR.into(),
+            ..Default::default()
+        })
+        .insert(StatisticsList)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle::from_section("Statistics", text_style.clone()));
+            for (label, value) in get_statistic_rows(&profile.stats) {
+                parent.spawn_bundle(TextBundle::from_section(
+                    format!("{}: {}", label, value),
+                    TextStyle { font_size: 24., ..text_style.clone() }
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(4.)),
+                    ..Default::default()
+                }));
+            }
+            parent.spawn_bundle(TextBundle::from_section(
+                "Click to go back",
+                TextStyle { font_size: 16., ..text_style.clone() }
+            ));
+        });
+}
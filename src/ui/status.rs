@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use crate::run::RunRng;
 use crate::units::{
     player::{Player, PlayerData},
     Unit
@@ -31,6 +32,7 @@ pub fn draw_status(
     status_query: Query<Entity, With<StatusBar>>,
     player_query: Query<&Unit, With<Player>>,
     player_data: Res<PlayerData>,
+    run_rng: Res<RunRng>,
     assets: Res<super::FontAssets>,
     mut ev_draw_cursor: EventReader<super::RedrawUIEvent>,
 ) {
@@ -40,8 +42,10 @@ pub fn draw_status(
 
         if let Ok(player) = player_query.get_single() {
             let s = format!(
-                "Level: {}",
-                player_data.level
+                "Level: {}  Coins: {}  Seed: {}",
+                player_data.level,
+                player_data.coins,
+                run_rng.seed
             );
             let color = Color::Rgba { red: 0.84, green: 0.85, blue: 0.84, alpha: 1. };
             commands
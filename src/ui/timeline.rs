@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::board::{Position, SIZE};
+use crate::graphics::{
+    TILE_SIZE, CURSOR_Z, UNIT_Z,
+    unit_renderer::{UnitSprites, get_sprite_idx}
+};
+use crate::states::GameState;
+use crate::units::{
+    Unit,
+    npc::{NPC, NPCQueue, SpawnOrder, get_turn_order},
+    player::Player
+};
+
+const ENTRY_SIZE: f32 = 32.;
+const ENTRY_GAP: f32 = 4.;
+const HIGHLIGHT_COLOR: Color = Color::Rgba { red: 1., green: 1., blue: 1., alpha: 0.3 };
+
+// entities shown in the strip, in the order they are going to act
+#[derive(Default)]
+pub struct Timeline {
+    order: Vec<Entity>
+}
+
+#[derive(Component)]
+pub struct TimelineEntry;
+
+#[derive(Component)]
+pub struct TimelineHighlight;
+
+pub fn draw_timeline(
+    mut commands: Commands,
+    mut timeline: ResMut<Timeline>,
+    game_state: Res<State<GameState>>,
+    entry_query: Query<Entity, With<TimelineEntry>>,
+    player_query: Query<Entity, With<Player>>,
+    npc_query: Query<(Entity, &Unit, &SpawnOrder), With<NPC>>,
+    unit_query: Query<&Unit>,
+    npc_queue: Res<NPCQueue>,
+    sprites: Option<Res<UnitSprites>>
+) {
+    let sprites = match sprites {
+        Some(s) => s,
+        None => return
+    };
+    let order: Vec<Entity> = match game_state.current() {
+        GameState::PlayerTurn => player_query.iter()
+            .chain(get_turn_order(npc_query.iter()))
+            .collect(),
+        GameState::NPCTurn => npc_queue.current.iter()
+            .chain(npc_queue.npcs.iter())
+            .copied()
+            .filter(|e| unit_query.get(*e).is_ok())
+            .collect(),
+        _ => Vec::new()
+    };
+    if order == timeline.order { return; }
+
+    for entity in entry_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    for (idx, entity) in order.iter().enumerate() {
+        let unit = match unit_query.get(*entity) {
+            Ok(u) => u,
+            _ => continue
+        };
+        let mut sprite = TextureAtlasSprite::new(get_sprite_idx(&unit.kind));
+        sprite.custom_size = Some(Vec2::splat(ENTRY_SIZE));
+        commands.spawn_bundle(SpriteSheetBundle {
+                sprite,
+                texture_atlas: sprites.0.clone(),
+                transform: Transform::from_translation(get_entry_translation(idx)),
+                ..Default::default()
+            })
+            .insert(TimelineEntry);
+    }
+    timeline.order = order;
+}
+
+pub fn hover_timeline(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<crate::camera::MainCamera>>,
+    timeline: Res<Timeline>,
+    unit_query: Query<&Position, With<Unit>>,
+    mut highlight_query: Query<(&mut Transform, &mut Visibility), With<TimelineHighlight>>
+) {
+    let (mut highlight_transform, mut visibility) = match highlight_query.get_single_mut() {
+        Ok(h) => h,
+        _ => {
+            commands.spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: HIGHLIGHT_COLOR,
+                        custom_size: Some(Vec2::splat(TILE_SIZE)),
+                        ..Default::default()
+                    },
+                    visibility: Visibility { is_visible: false },
+                    ..Default::default()
+                })
+                .insert(TimelineHighlight);
+            return;
+        }
+    };
+
+    let hovered = super::input::mouse_to_world(&windows, &camera_query)
+        .and_then(get_hovered_idx)
+        .and_then(|idx| timeline.order.get(idx))
+        .and_then(|entity| unit_query.get(*entity).ok());
+
+    visibility.is_visible = hovered.is_some();
+    if let Some(position) = hovered {
+        // just under the unit sprite
+        highlight_transform.translation = Vec3::new(
+            (position.v.x as f32 + 0.5) * TILE_SIZE,
+            (position.v.y as f32 + 0.5) * TILE_SIZE,
+            UNIT_Z - 1.
+        );
+    }
+}
+
+fn get_hovered_idx(world_pos: Vec2) -> Option<usize> {
+    let y = SIZE as f32 * TILE_SIZE + ENTRY_SIZE;
+    if (world_pos.y - y).abs() > ENTRY_SIZE / 2. || world_pos.x < 0. { return None; }
+    let step = ENTRY_SIZE + ENTRY_GAP;
+    if world_pos.x % step > ENTRY_SIZE { return None; }
+    Some((world_pos.x / step) as usize)
+}
+
+fn get_entry_translation(idx: usize) -> Vec3 {
+    Vec3::new(
+        ENTRY_SIZE / 2. + idx as f32 * (ENTRY_SIZE + ENTRY_GAP),
+        SIZE as f32 * TILE_SIZE + ENTRY_SIZE,
+        CURSOR_Z
+    )
+}
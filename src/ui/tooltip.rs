@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::board::{
+    Position, SIZE,
+    tile::{Tile, get_tile_description}
+};
+use crate::graphics::{TILE_SIZE, palette::Palette};
+use crate::items::{Item, data::get_item_description};
+use crate::units::{
+    Unit,
+    data::get_unit_rank,
+    player::{Player, PlayerData}
+};
+use crate::vectors::Vector2Int;
+
+const BACKGROUND_COLOR: Color = Color::Rgba { red: 0.11, green: 0.09, blue: 0.08, alpha: 0.9 };
+const EMPTY_CELL_COLOR: Color = Color::Rgba { red: 1., green: 1., blue: 1., alpha: 0.1 };
+const CELL_SIZE: f32 = 8.;
+const OFFSET: f32 = 16.;
+
+// board square the tooltip is currently drawn for
+#[derive(Default)]
+pub struct Tooltip {
+    v: Option<Vector2Int>
+}
+
+#[derive(Component)]
+pub struct TooltipNode;
+
+pub fn clear_tooltip(
+    mut commands: Commands,
+    mut tooltip: ResMut<Tooltip>,
+    query: Query<Entity, With<TooltipNode>>
+) {
+    tooltip.v = None;
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn draw_tooltip(
+    mut commands: Commands,
+    mut tooltip: ResMut<Tooltip>,
+    mut ev_redraw: EventReader<super::RedrawUIEvent>,
+    windows: Res<Windows>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<crate::camera::MainCamera>>,
+    mut node_query: Query<(Entity, &mut Style), With<TooltipNode>>,
+    unit_query: Query<(&Unit, &Position, Option<&Player>)>,
+    tile_query: Query<(&Tile, &Position)>,
+    item_query: Query<(&Item, &Position)>,
+    player_data: Res<PlayerData>,
+    assets: Res<super::FontAssets>,
+    palette: Res<Palette>
+) {
+    let window = match windows.get_primary() {
+        Some(w) => w,
+        None => return
+    };
+    let screen_pos = match window.cursor_position() {
+        Some(p) => p,
+        None => Vec2::ZERO
+    };
+    let position = get_tooltip_position(screen_pos, window.width());
+
+    let hovered = super::input::mouse_to_world(&windows, &camera_query)
+        .map(|w| Vector2Int::new(
+            (w.x / TILE_SIZE).floor() as i32,
+            (w.y / TILE_SIZE).floor() as i32
+        ))
+        .filter(|v| v.x >= 0 && v.y >= 0 && v.x < SIZE as i32 && v.y < SIZE as i32);
+
+    let redraw = ev_redraw.iter().count() > 0;
+    if hovered != tooltip.v || redraw {
+        for (entity, _) in node_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        tooltip.v = hovered;
+        if let Some(v) = hovered {
+            let mut lines = Vec::new();
+            let mut pattern = None;
+            if let Some((unit, _, player)) = unit_query.iter().find(|(_, p, _)| p.v == v) {
+                match player {
+                    Some(_) => {
+                        lines.push(format!("You - {:?} pattern", player_data.pattern_kind));
+                        pattern = Some(player_data.current_behaviour.pattern.clone());
+                    },
+                    None => {
+                        lines.push(format!("{:?} - rank {}", unit.kind, get_unit_rank(&unit.kind)));
+                        pattern = Some(unit.behaviour.pattern.clone());
+                    }
+                }
+            }
+            if let Some((item, _)) = item_query.iter().find(|(_, p)| p.v == v) {
+                lines.push(format!("{:?}: {}", item.kind, get_item_description(item.kind)));
+            }
+            if let Some((tile, _)) = tile_query.iter().find(|(_, p)| p.v == v) {
+                if let Some(description) = get_tile_description(&tile.kind) {
+                    lines.push(description.to_string());
+                }
+            }
+            if !lines.is_empty() {
+                spawn_tooltip(&mut commands, position, lines, pattern, &assets, &palette);
+            }
+        }
+        return;
+    }
+
+    // follow the mouse
+    for (_, mut style) in node_query.iter_mut() {
+        style.position = position;
+    }
+}
+
+fn spawn_tooltip(
+    commands: &mut Commands,
+    position: UiRect<Val>,
+    lines: Vec<String>,
+    pattern: Option<Vec<Vector2Int>>,
+    assets: &super::FontAssets,
+    palette: &Palette
+) {
+    commands.spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position,
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::FlexStart,
+                padding: UiRect::all(Val::Px(8.)),
+                ..Default::default()
+            },
+            color: BACKGROUND_COLOR.into(),
+            focus_policy: FocusPolicy::Pass,
+            ..Default::default()
+        })
+        .insert(TooltipNode)
+        .with_children(|parent| {
+            for line in lines {
+                parent.spawn_bundle(TextBundle::from_section(
+                    line,
+                    TextStyle {
+                        color: palette.status,
+                        font: assets.font.clone(),
+                        font_size: 24.
+                    }
+                ));
+            }
+            if let Some(pattern) = pattern {
+                spawn_diagram(parent, &pattern, palette);
+            }
+        });
+}
+
+// the unit in the middle and every square of its move pattern around it
+pub fn spawn_diagram(
+    parent: &mut ChildBuilder,
+    pattern: &[Vector2Int],
+    palette: &Palette
+) {
+    let range = pattern.iter()
+        .map(|v| v.x.abs().max(v.y.abs()))
+        .max()
+        .unwrap_or(0)
+        .min(SIZE as i32 - 1);
+    let side = (2 * range + 1) as f32 * CELL_SIZE;
+
+    parent.spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(side), Val::Px(side)),
+                margin: UiRect::new(Val::Px(0.), Val::Px(0.), Val::Px(8.), Val::Px(0.)),
+                ..Default::default()
+            },
+            color: EMPTY_CELL_COLOR.into(),
+            focus_policy: FocusPolicy::Pass,
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            let cells = pattern.iter()
+                .filter(|v| v.x.abs() <= range && v.y.abs() <= range)
+                .map(|v| (*v, palette.cursor))
+                .chain([(Vector2Int::new(0, 0), palette.status)]);
+            for (v, color) in cells {
+                parent.spawn_bundle(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            left: Val::Px((v.x + range) as f32 * CELL_SIZE),
+                            bottom: Val::Px((v.y + range) as f32 * CELL_SIZE),
+                            ..Default::default()
+                        },
+                        size: Size::new(Val::Px(CELL_SIZE - 1.), Val::Px(CELL_SIZE - 1.)),
+                        ..Default::default()
+                    },
+                    color: (*color.clone().set_a(1.)).into(),
+                    focus_policy: FocusPolicy::Pass,
+                    ..Default::default()
+                });
+            }
+        });
+}
+
+// keeps the tooltip on the screen side closer to the center
+fn get_tooltip_position(screen_pos: Vec2, width: f32) -> UiRect<Val> {
+    let mut position = UiRect {
+        bottom: Val::Px(screen_pos.y + OFFSET),
+        ..Default::default()
+    };
+    if screen_pos.x > width / 2. {
+        position.right = Val::Px(width - screen_pos.x + OFFSET);
+    } else {
+        position.left = Val::Px(screen_pos.x + OFFSET);
+    }
+    position
+}
@@ -0,0 +1,193 @@
+use bevy::prelude::*;
+
+use crate::actions::{Action, Actions};
+use crate::command::CommandEvent;
+use crate::items::{InventoryPrompt, ItemTargeting};
+use crate::run::{FloorSnapshot, RunLabel};
+use crate::states::{AnimationState, FadeState, GameState};
+use crate::ui::{RedrawUIEvent, input::InputAssets};
+
+// floor states the player could act from, the last one is the current state
+#[derive(Default)]
+pub struct UndoStack(pub Vec<FloorSnapshot>);
+
+pub struct UndoPlugin;
+
+impl Plugin for UndoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UndoStack>();
+        // runs before the turn systems, so the snapshot is never taken halfway through a move
+        app.add_system(record_and_undo.exclusive_system().at_start().after(RunLabel::RestoreFloor));
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MapGenerate)
+                .with_system(clear_undo)
+        );
+        app.add_system_set(
+            SystemSet::on_enter(GameState::MainMenu)
+                .with_system(clear_undo)
+        );
+    }
+}
+
+// the player can act and nothing is waiting to be resolved
+fn is_ready(world: &World) -> bool {
+    world.resource::<State<GameState>>().current() == &GameState::PlayerTurn
+        && world.resource::<State<AnimationState>>().current() == &AnimationState::Idle
+        && world.resource::<State<FadeState>>().current() == &FadeState::Hidden
+        && world.resource::<InventoryPrompt>().item.is_none()
+        && !world.resource::<ItemTargeting>().is_active()
+        && world.resource::<Events<CommandEvent>>().is_empty()
+}
+
+// undo goes back to the previous state the player could act from,
+// the statistics and achievements of the undone moves are kept
+fn record_and_undo(world: &mut World) {
+    if !is_ready(world) { return; }
+
+    if world.resource::<Actions>().just_pressed(Action::Undo) {
+        let mut stack = world.resource_mut::<UndoStack>();
+        if stack.0.len() < 2 { return; }
+        stack.0.pop();
+        let previous = stack.0.last().unwrap().clone();
+        previous.restore(world);
+
+        // the old entities are gone
+        world.resource_mut::<InputAssets>().selected_npc = None;
+        world.resource_mut::<Events<RedrawUIEvent>>().send(RedrawUIEvent);
+        return;
+    }
+
+    let snapshot = FloorSnapshot::take(world);
+    let mut stack = world.resource_mut::<UndoStack>();
+    if stack.0.last() != Some(&snapshot) {
+        stack.0.push(snapshot);
+    }
+}
+
+fn clear_undo(mut stack: ResMut<UndoStack>) {
+    stack.0.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actions::{InputBindings, update_actions};
+    use crate::board::{Blocker, Position};
+    use crate::units::{
+        Unit, UnitKind,
+        data::get_unit_behaviour,
+        npc::{NPC, SpawnOrder},
+        player::{Player, PlayerData},
+        status::StatusEffects
+    };
+    use crate::vectors::Vector2Int;
+    use super::*;
+
+    fn setup() -> App {
+        let mut app = App::new();
+        app.insert_resource(InputBindings::default());
+        app.init_resource::<Input<KeyCode>>();
+        app.init_resource::<Input<MouseButton>>();
+        app.init_resource::<Gamepads>();
+        app.init_resource::<Input<GamepadButton>>();
+        app.init_resource::<Actions>();
+        app.insert_resource(State::new(GameState::PlayerTurn));
+        app.insert_resource(State::new(AnimationState::Idle));
+        app.insert_resource(State::new(FadeState::Hidden));
+        app.init_resource::<InventoryPrompt>();
+        app.init_resource::<ItemTargeting>();
+        app.insert_resource(InputAssets { selected_npc: None, clicked_button: None });
+        app.init_resource::<PlayerData>();
+        app.init_resource::<UndoStack>();
+        app.add_event::<CommandEvent>();
+        app.add_event::<RedrawUIEvent>();
+        app.add_system(update_actions);
+        app.add_system(record_and_undo.exclusive_system().at_end());
+
+        spawn_unit(&mut app, UnitKind::Player, Vector2Int::new(0, 0)).insert(Player);
+        spawn_unit(&mut app, UnitKind::Wolf, Vector2Int::new(2, 2)).insert(NPC).insert(SpawnOrder(0));
+        app
+    }
+
+    fn spawn_unit(app: &mut App, kind: UnitKind, v: Vector2Int) -> bevy::ecs::world::EntityMut<'_> {
+        let mut entity = app.world.spawn();
+        entity.insert(Position { v })
+            .insert(Blocker { is_targetable: true })
+            .insert(Unit { ap: 1, behaviour: get_unit_behaviour(&kind), kind, effects: StatusEffects::default() });
+        entity
+    }
+
+    fn player_v(app: &mut App) -> Vector2Int {
+        app.world.query_filtered::<&Position, With<Player>>().single(&app.world).v
+    }
+
+    fn npc_count(app: &mut App) -> usize {
+        app.world.query::<&NPC>().iter(&app.world).count()
+    }
+
+    fn move_player(app: &mut App, v: Vector2Int) {
+        let mut query = app.world.query_filtered::<&mut Position, With<Player>>();
+        query.single_mut(&mut app.world).v = v;
+        app.update();
+    }
+
+    fn press_undo(app: &mut App) {
+        app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Z);
+        app.update();
+        let mut keys = app.world.resource_mut::<Input<KeyCode>>();
+        keys.release(KeyCode::Z);
+        keys.clear();
+    }
+
+    #[test]
+    fn undo_steps_back_through_the_moves() {
+        let mut app = setup();
+        app.update();
+        move_player(&mut app, Vector2Int::new(1, 1));
+        // a capture
+        let npc = app.world.query_filtered::<Entity, With<NPC>>().single(&app.world);
+        app.world.despawn(npc);
+        move_player(&mut app, Vector2Int::new(2, 2));
+        assert_eq!(npc_count(&mut app), 0);
+
+        press_undo(&mut app);
+        assert_eq!(player_v(&mut app), Vector2Int::new(1, 1));
+        assert_eq!(npc_count(&mut app), 1);
+
+        press_undo(&mut app);
+        assert_eq!(player_v(&mut app), Vector2Int::new(0, 0));
+
+        // nothing left to undo
+        press_undo(&mut app);
+        assert_eq!(player_v(&mut app), Vector2Int::new(0, 0));
+        assert_eq!(app.world.resource::<UndoStack>().0.len(), 1);
+    }
+
+    #[test]
+    fn nothing_is_recorded_while_animating() {
+        let mut app = setup();
+        app.update();
+        app.insert_resource(State::new(AnimationState::Animating));
+        move_player(&mut app, Vector2Int::new(1, 1));
+        assert_eq!(app.world.resource::<UndoStack>().0.len(), 1);
+
+        press_undo(&mut app);
+        assert_eq!(player_v(&mut app), Vector2Int::new(1, 1));
+    }
+
+    #[test]
+    fn saved_floor_restores_after_a_round_trip() {
+        let mut app = setup();
+        app.update();
+        let saved = ron::to_string(app.world.resource::<UndoStack>().0.last().unwrap()).unwrap();
+
+        move_player(&mut app, Vector2Int::new(1, 1));
+        let npc = app.world.query_filtered::<Entity, With<NPC>>().single(&app.world);
+        app.world.despawn(npc);
+
+        let floor: FloorSnapshot = ron::from_str(&saved).unwrap();
+        floor.restore(&mut app.world);
+        assert_eq!(player_v(&mut app), Vector2Int::new(0, 0));
+        assert_eq!(npc_count(&mut app), 1);
+        assert!(FloorSnapshot::take(&mut app.world) == floor);
+    }
+}
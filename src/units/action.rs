@@ -1,6 +1,20 @@
 use crate::vectors::{Vector2Int, vector_line};
 use crate::board::{Blocker, Position};
 
+use super::behaviour::MoveKind;
+
+pub type Validator = fn(
+    source: Vector2Int,
+    target: Vector2Int,
+    blockers: &Vec<(&Position, &Blocker)>
+) -> bool;
+
+pub fn get_validator(kind: MoveKind) -> Validator {
+    match kind {
+        MoveKind::Step => walk_validator,
+        MoveKind::Jump => jump_validator
+    }
+}
 
 pub fn walk_validator(
     source: Vector2Int,
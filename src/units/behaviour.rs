@@ -4,17 +4,19 @@ use crate::board::{
     Position
 };
 use crate::vectors::{DIAGONAL_DIRECTIONS, ORTHO_DIRECTIONS, Vector2Int};
-// use super::action::{ActionType, ActionValidator, get_validator};
+use super::action::get_validator;
+
+// how a unit gets to the target - steps are stopped by blockers on the way
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveKind {
+    Step,
+    Jump
+}
 
 #[derive(Clone)]
 pub struct Behaviour {
     pub pattern: Vec::<Vector2Int>,
-    // pub action_type: ActionType,
-    pub validator: fn(
-        source: Vector2Int,
-        target: Vector2Int,
-        blockers: &Vec<(&Position, &Blocker)>
-    ) -> bool
+    pub move_kind: MoveKind
 }
 
 impl Behaviour {
@@ -25,7 +27,7 @@ impl Behaviour {
         blockers: &Vec<(&Position, &Blocker)>
     ) -> Vec::<Vector2Int> {
         let mut positions = Vec::new();
-        // let validator = get_validator(&self.action_type);
+        let validator = get_validator(self.move_kind);
         for v in &self.pattern {
             let p = source + *v;
             if !board.tiles.contains_key(&p) { continue; }
@@ -34,7 +36,7 @@ impl Behaviour {
                 if !blocker.1.is_targetable { continue; }
             }
 
-            if (self.validator)(source, p, blockers) { positions.push(p); }
+            if validator(source, p, blockers) { positions.push(p); }
         }
         positions
     }
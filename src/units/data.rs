@@ -1,8 +1,7 @@
 use crate::board;
 
-use super::action::{walk_validator, jump_validator};
 use super::behaviour::{
-    Behaviour, get_omni_pattern, get_ortho_pattern, get_knight_pattern, get_ram_pattern,
+    Behaviour, MoveKind, get_omni_pattern, get_ortho_pattern, get_knight_pattern, get_ram_pattern,
     get_player_pattern, get_diagonal_pattern
 };
 use super::{UnitKind, Unit};
@@ -12,61 +11,61 @@ pub fn get_unit_behaviour(kind: &UnitKind) -> Behaviour {
         UnitKind::Player => {
             Behaviour {
                 pattern: get_player_pattern(),
-                validator: walk_validator
+                move_kind: MoveKind::Step
             }
         },
         UnitKind::Ram => {
             Behaviour {
                 pattern: get_ram_pattern(),
-                validator: walk_validator
+                move_kind: MoveKind::Step
             }
         },
         UnitKind::Frog => {
             Behaviour {
                 pattern: get_diagonal_pattern(1),
-                validator: jump_validator
+                move_kind: MoveKind::Jump
             }
         },
         UnitKind::Bear => {
             Behaviour {
                 pattern: get_ortho_pattern(2),
-                validator: walk_validator
+                move_kind: MoveKind::Step
             }
         },
         UnitKind::Hen => {
             Behaviour {
                 pattern: get_ortho_pattern(1),
-                validator: walk_validator
+                move_kind: MoveKind::Step
             }
         },
         // UnitKind::Cat => {
         //     Behaviour {
         //         pattern: get_cat_pattern(),
-        //         validator: jump_validator
+        //         move_kind: MoveKind::Jump
         //     }
         // },
         UnitKind::Stork => {
             Behaviour {
                 pattern: get_knight_pattern(),
-                validator: jump_validator
+                move_kind: MoveKind::Jump
             }
         },       
         UnitKind::Fox => {
             Behaviour {
                 pattern: get_diagonal_pattern(board::SIZE),
-                validator: walk_validator
+                move_kind: MoveKind::Step
             }
         },
         UnitKind::Wolf => {
             Behaviour {
                 pattern: get_ortho_pattern(board::SIZE),
-                validator: walk_validator
+                move_kind: MoveKind::Step
             }
         },
         // UnitKind::Puma => {
         //     Behaviour {
         //         pattern: get_puma_pattern(),
-        //         validator: jump_validator
+        //         move_kind: MoveKind::Jump
         //     }
         // }
     }
@@ -87,6 +86,34 @@ pub fn get_unit_rank(kind: &UnitKind) -> u32 {
     }
 }
 
+// movement quirks the pattern alone doesn't show
+pub fn get_unit_rules(kind: &UnitKind) -> &'static str {
+    match kind {
+        UnitKind::Player => "Captures give you the victim's pattern",
+        UnitKind::Ram => "Can also charge two squares back",
+        UnitKind::Frog => "Jumps diagonally over anything",
+        UnitKind::Bear => "Walks up to two squares in a line",
+        UnitKind::Hen => "Walks a single square",
+        UnitKind::Stork => "Jumps like a knight",
+        UnitKind::Fox => "Slides diagonally until blocked",
+        UnitKind::Wolf => "Slides in a line until blocked"
+    }
+}
+
+// faster units act earlier in the npc turn
+pub fn get_unit_speed(kind: &UnitKind) -> u32 {
+    match kind {
+        UnitKind::Fox => 3,
+        UnitKind::Wolf => 3,
+        UnitKind::Stork => 2,
+        UnitKind::Frog => 2,
+        UnitKind::Ram => 1,
+        UnitKind::Bear => 1,
+        UnitKind::Hen => 1,
+        UnitKind::Player => 0,
+    }
+}
+
 pub fn get_npc_types() -> Vec<UnitKind> {
     vec![
         UnitKind::Ram,
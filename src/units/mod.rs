@@ -1,8 +1,11 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
 use crate::command::{CommandEvent, CommandType};
+use crate::events::GameEvent;
 
+use crate::run::RunRng;
 use crate::states::{AnimationState, GameState};
 use crate::board::{
     Blocker,
@@ -14,9 +17,10 @@ use crate::vectors::Vector2Int;
 
 mod action;
 pub mod behaviour;
-mod data;
+pub mod data;
 pub mod npc;
 pub mod player;
+pub mod status;
 mod utils;
 
 const BASE_AP: u8 = 1;
@@ -26,8 +30,9 @@ pub struct UnitsPlugin;
 impl Plugin for UnitsPlugin {
     fn build(&self, app: &mut App) {
         app.add_startup_system(player::reset_player_data);
+        // the run is cleaned up when returning to the menu, after dying or quitting
         app.add_system_set(
-            SystemSet::on_exit(GameState::GameOver)
+            SystemSet::on_enter(GameState::MainMenu)
                 .with_system(player::reset_player_data)
                 .with_system(clear_units)
         );
@@ -41,6 +46,8 @@ impl Plugin for UnitsPlugin {
         );
 
         app.add_event::<player::MovePlayerEvent>();
+        app.add_event::<player::SelectCardEvent>();
+        app.add_system(player::land_after_command);
 
         app.add_system_set(
             SystemSet::on_enter(GameState::PlayerTurn)
@@ -49,6 +56,7 @@ impl Plugin for UnitsPlugin {
         app.add_system_set(
             SystemSet::on_update(GameState::PlayerTurn)
                 .with_system(player::move_player)
+                .with_system(player::select_card)
                 .with_system(player::player_status)
         );
 
@@ -73,7 +81,7 @@ impl Plugin for UnitsPlugin {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UnitKind {
     Player,
     Ram,
@@ -85,17 +93,12 @@ pub enum UnitKind {
     Wolf
 }
 
-pub enum UnitState {
-    Active,
-    Paused
-}
-
 #[derive(Component)]
 pub struct Unit {
     pub ap: u8,
     pub behaviour: behaviour::Behaviour,
     pub kind: UnitKind,
-    pub state: UnitState
+    pub effects: status::StatusEffects
 }
 
 impl Unit {
@@ -105,14 +108,17 @@ impl Unit {
         self.ap = self.ap.saturating_sub(1);
     }
     
-    pub fn handle_turn_start(&mut self) {
-        match self.state {
-            UnitState::Active => self.ap = BASE_AP,
-            UnitState::Paused => {
-                self.state = UnitState::Active;
-                self.ap = 0
-            }
-        };
+    // whether the unit is going to move at its next turn start
+    pub fn can_act_next_turn(&self) -> bool {
+        let start = self.effects.preview(BASE_AP);
+        !start.dead && start.ap > 0
+    }
+
+    // returns false when the unit doesn't survive its status effects
+    pub fn handle_turn_start(&mut self) -> bool {
+        let start = self.effects.tick(BASE_AP);
+        self.ap = start.ap;
+        !start.dead
     }
 }
 
@@ -121,9 +127,13 @@ fn spawn_units(
     mut game_state: ResMut<State<GameState>>,
     board_query: Query<&Board>,
     blocker_query: Query<&Position, With<Blocker>>,
-    mut player_data: ResMut<player::PlayerData>
+    mut player_data: ResMut<player::PlayerData>,
+    mut run_rng: ResMut<RunRng>,
+    mut ev_game: EventWriter<GameEvent>
 ) {
     player_data.level += 1;
+    player_data.reveal_intents = false;
+    ev_game.send(GameEvent::LevelStarted { level: player_data.level });
 
     let board = match board_query.get_single() {
         Ok(b) => b,
@@ -138,7 +148,7 @@ fn spawn_units(
         None => ()
     };
 
-    npc::spawn_npcs(&mut commands, &mut blocker_positions, &board, (player_data.level as f32).powf(1.5) as u32);
+    npc::spawn_npcs(&mut commands, &mut blocker_positions, &board, (player_data.level as f32).powf(1.5) as u32, &mut run_rng.rng);
     game_state.set(GameState::PlayerTurn);
 }
 
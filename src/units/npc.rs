@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use rand::Rng;
+use std::cmp::Reverse;
 use std::collections::VecDeque;
 
 use crate::board::{
@@ -7,12 +9,18 @@ use crate::board::{
     tile::{Tile, TileInteractionEvent}
 };
 use crate::command::{CommandEvent, CommandType};
+use crate::events::GameEvent;
 use crate::states::{GameState, AnimationState};
 use crate::vectors::Vector2Int;
 
-use super::data::get_unit_behaviour;
+use super::data::{get_unit_behaviour, get_unit_speed};
 use super::player::Player;
-use super::Unit;
+use super::{Unit, UnitKind};
+
+pub struct PlannedMove {
+    pub kind: UnitKind,
+    pub to: Vector2Int
+}
 
 pub struct NPCQueue {
     pub npcs: VecDeque<Entity>,
@@ -22,6 +30,10 @@ pub struct NPCQueue {
 #[derive(Component)]
 pub struct NPC;
 
+// breaks initiative ties between npcs of the same speed
+#[derive(Component)]
+pub struct SpawnOrder(pub usize);
+
 pub fn tick(
     mut game_state: ResMut<State<GameState>>,
     mut npc_queue: ResMut<NPCQueue>,
@@ -56,7 +68,9 @@ pub fn move_npc(
     blocker_query: Query<(&Position, &Blocker), (Without<NPC>, Without<Player>)>,
     player_query: Query<(&Position, &Blocker), With<Player>>,
     mut game_state: ResMut<State<GameState>>,
-    mut animation_state: ResMut<State<AnimationState>>
+    mut animation_state: ResMut<State<AnimationState>>,
+    mut ev_command: EventWriter<CommandEvent>,
+    mut ev_game: EventWriter<GameEvent>
 ) {
     if npc_queue.current.is_some() { return; }
 
@@ -69,7 +83,10 @@ pub fn move_npc(
     };
 
     if let Ok((_, mut unit, _)) = npc_query.get_mut(entity) {
-        unit.handle_turn_start();
+        if !unit.handle_turn_start() {
+            ev_command.send(CommandEvent(CommandType::KillUnit(entity)));
+            return;
+        }
         if unit.ap == 0 { return; }
     }
 
@@ -99,10 +116,16 @@ pub fn move_npc(
         );
     }
    
-    if let Ok((mut position, _, _)) = npc_query.get_mut(entity) {  
+    if let Ok((mut position, unit, _)) = npc_query.get_mut(entity) {  
         match new_position_v {
             Some(v) => {
-                position.v = v;
+                let from = std::mem::replace(&mut position.v, v);
+                ev_game.send(GameEvent::UnitMoved {
+                    entity,
+                    move_kind: unit.behaviour.move_kind,
+                    from,
+                    to: v
+                });
             }
             _ => ()
         }
@@ -113,23 +136,32 @@ pub fn move_npc(
 }
 
 pub fn start_npc_turn(
-    npc_query: Query<Entity, With<NPC>>,
+    npc_query: Query<(Entity, &Unit, &SpawnOrder), With<NPC>>,
     mut queue: ResMut<NPCQueue>
 ) {
-    let npcs = npc_query.iter().collect();
-    queue.npcs = npcs;
+    queue.npcs = get_turn_order(npc_query.iter()).into();
     queue.current = None;
 }
 
+// faster npcs go first, equal speeds keep the spawn order
+pub fn get_turn_order<'a>(
+    npcs: impl Iterator<Item=(Entity, &'a Unit, &'a SpawnOrder)>
+) -> Vec<Entity> {
+    let mut npcs: Vec<_> = npcs.collect();
+    npcs.sort_by_key(|(_, unit, order)| (Reverse(get_unit_speed(&unit.kind)), order.0));
+    npcs.into_iter().map(|(e, _, _)| e).collect()
+}
+
 pub fn spawn_npcs(
     commands: &mut Commands,
     blocker_positions: &mut Vec<Vector2Int>,
     board: &Board,
-    rank_sum: u32
+    rank_sum: u32,
+    rng: &mut impl Rng
 ) { 
-    let kinds = super::utils::get_npc_set(rank_sum);
-    for kind in kinds {
-        let position = get_spawn_position(blocker_positions, board);
+    let kinds = super::utils::get_npc_set(rank_sum, rng);
+    for (idx, kind) in kinds.into_iter().enumerate() {
+        let position = get_spawn_position(blocker_positions, board, rng);
         if position.is_none() { continue; }
 
         blocker_positions.push(position.unwrap());
@@ -137,16 +169,87 @@ pub fn spawn_npcs(
         commands.spawn()
             .insert(Position { v: position.unwrap() })
             .insert(NPC)
+            .insert(SpawnOrder(idx))
             .insert(Blocker { is_targetable: true })
             .insert(Unit { 
                 ap: super::BASE_AP,
                 behaviour: get_unit_behaviour(&kind),
                 kind: kind,
-                state: super::UnitState::Active
+                effects: super::status::StatusEffects::default()
             });
     }
 }
 
+// replays the coming npc turn with the same rules as move_npc,
+// so the plan holds as long as the board doesn't change
+pub fn plan_npc_turn(
+    npcs: &[(Entity, &Unit, Vector2Int)],
+    walls: &[(&Position, &Blocker)],
+    player: Option<Vector2Int>,
+    mut armor: u8,
+    board: &Board
+) -> Vec<PlannedMove> {
+    let mut positions: Vec<(Entity, Vector2Int)> = npcs.iter()
+        .map(|(e, _, v)| (*e, *v))
+        .collect();
+    let mut player = player;
+    let mut moves = Vec::new();
+
+    for (entity, unit, _) in npcs {
+        let player_v = match player {
+            Some(v) => v,
+            None => break
+        };
+        let source = match positions.iter().find(|(e, _)| e == entity) {
+            Some((_, v)) => *v,
+            // captured earlier in the turn
+            None => continue
+        };
+        if unit.effects.preview(super::BASE_AP).dead {
+            positions.retain(|(e, _)| e != entity);
+            continue;
+        }
+        if !unit.can_act_next_turn() { continue; }
+
+        let player_position = Position { v: player_v };
+        let npc_positions: Vec<Position> = positions.iter()
+            .map(|(_, v)| Position { v: *v })
+            .collect();
+        let unit_blocker = Blocker { is_targetable: true };
+        let mut blockers = walls.to_vec();
+        blockers.push((&player_position, &unit_blocker));
+        blockers.extend(npc_positions.iter().map(|p| (p, &unit_blocker)));
+
+        let to = match get_best_move(
+            unit,
+            source,
+            board,
+            &blockers,
+            &player_position,
+            npc_positions.iter().collect()
+        ) {
+            Some(v) => v,
+            None => continue
+        };
+        moves.push(PlannedMove { kind: unit.kind, to });
+
+        positions.retain(|(e, v)| e == entity || *v != to);
+        if let Some(p) = positions.iter_mut().find(|(e, _)| e == entity) {
+            p.1 = to;
+        }
+        if to == player_v {
+            match armor {
+                0 => player = None,
+                _ => {
+                    armor -= 1;
+                    positions.retain(|(e, _)| e != entity);
+                }
+            }
+        }
+    }
+    moves
+}
+
 fn get_best_move(
     unit: &Unit,
     source: Vector2Int,
@@ -156,7 +259,10 @@ fn get_best_move(
     npc_positions: Vec<&Position>
 ) -> Option<Vector2Int> {
 
-    let positions = unit.behaviour.possible_positions(source, board, blockers);
+    let positions = unit.effects.restrict_moves(
+        unit.behaviour.possible_positions(source, board, blockers),
+        blockers
+    );
 
     if positions.len() == 0 {
         return None;
@@ -173,4 +279,139 @@ fn get_best_move(
     
     rated.sort_by_key(|a| (100. * a.0) as u32);
     Some(rated[0].1)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::units::status::{StatusEffect, StatusEffects, StatusKind};
+    use super::*;
+
+    // 5x5 board with the player in the corner and a wall in the middle
+    fn setup() -> App {
+        let mut app = App::new();
+        app.insert_resource(State::new(GameState::NPCTurn));
+        app.insert_resource(State::new(AnimationState::Idle));
+        app.insert_resource(NPCQueue { npcs: VecDeque::new(), current: None });
+        app.add_event::<CommandEvent>();
+        app.add_event::<GameEvent>();
+        app.add_system(move_npc);
+
+        let tiles: HashMap<Vector2Int, Entity> = (0..25)
+            .map(|idx| (Vector2Int::new(idx % 5, idx / 5), app.world.spawn().id()))
+            .collect();
+        app.world.spawn().insert(Board { tiles, stair_v: Vector2Int::new(4, 4) });
+        app.world.spawn()
+            .insert(Player)
+            .insert(Position { v: Vector2Int::new(0, 0) })
+            .insert(Blocker { is_targetable: true });
+        app.world.spawn()
+            .insert(Position { v: Vector2Int::new(1, 2) })
+            .insert(Blocker { is_targetable: false });
+        app
+    }
+
+    fn spawn_npc(app: &mut App, kind: UnitKind, v: Vector2Int, order: usize) -> Entity {
+        app.world.spawn()
+            .insert(Position { v })
+            .insert(NPC)
+            .insert(SpawnOrder(order))
+            .insert(Blocker { is_targetable: true })
+            .insert(Unit {
+                ap: 0,
+                behaviour: get_unit_behaviour(&kind),
+                kind,
+                effects: StatusEffects::default()
+            })
+            .id()
+    }
+
+    fn turn_order(app: &mut App) -> Vec<Entity> {
+        let mut query = app.world.query::<(Entity, &Unit, &SpawnOrder)>();
+        get_turn_order(query.iter(&app.world))
+    }
+
+    fn plan(app: &mut App) -> Vec<(UnitKind, Vector2Int)> {
+        let order = turn_order(app);
+        let mut wall_query = app.world.query_filtered::<(&Position, &Blocker), Without<Unit>>();
+        let mut board_query = app.world.query::<&Board>();
+        let world = &app.world;
+        let npcs: Vec<(Entity, &Unit, Vector2Int)> = order.iter()
+            .map(|e| (*e, world.get::<Unit>(*e).unwrap(), world.get::<Position>(*e).unwrap().v))
+            .collect();
+        let walls: Vec<_> = wall_query.iter(world).collect();
+        let board = board_query.single(world);
+        plan_npc_turn(&npcs, &walls, Some(Vector2Int::new(0, 0)), 0, board)
+            .into_iter()
+            .map(|planned| (planned.kind, planned.to))
+            .collect()
+    }
+
+    // runs the whole npc turn, one npc per frame, skipping the move animations
+    fn play_turn(app: &mut App) -> Vec<(UnitKind, Vector2Int)> {
+        let order = turn_order(app);
+        let count = order.len();
+        app.world.resource_mut::<NPCQueue>().npcs = order.into();
+        let mut moves = Vec::new();
+        let mut reader = app.world.resource::<Events<GameEvent>>().get_reader();
+        for _ in 0..count {
+            app.update();
+            app.world.resource_mut::<NPCQueue>().current = None;
+            // events only last two frames
+            let events = app.world.resource::<Events<GameEvent>>();
+            for ev in reader.iter(events) {
+                if let GameEvent::UnitMoved { entity, to, .. } = *ev {
+                    moves.push((app.world.get::<Unit>(entity).unwrap().kind, to));
+                }
+            }
+        }
+        moves
+    }
+
+    #[test]
+    fn faster_npcs_go_first() {
+        let mut app = setup();
+        let hen = spawn_npc(&mut app, UnitKind::Hen, Vector2Int::new(4, 0), 0);
+        let bear = spawn_npc(&mut app, UnitKind::Bear, Vector2Int::new(4, 1), 3);
+        let fox = spawn_npc(&mut app, UnitKind::Fox, Vector2Int::new(4, 2), 4);
+        let frog = spawn_npc(&mut app, UnitKind::Frog, Vector2Int::new(4, 3), 2);
+        let wolf = spawn_npc(&mut app, UnitKind::Wolf, Vector2Int::new(4, 4), 1);
+        assert_eq!(turn_order(&mut app), vec![wolf, fox, frog, hen, bear]);
+    }
+
+    #[test]
+    fn equal_speeds_keep_the_spawn_order() {
+        let mut app = setup();
+        let ram = spawn_npc(&mut app, UnitKind::Ram, Vector2Int::new(4, 0), 2);
+        let hen = spawn_npc(&mut app, UnitKind::Hen, Vector2Int::new(4, 1), 0);
+        let bear = spawn_npc(&mut app, UnitKind::Bear, Vector2Int::new(4, 2), 1);
+        assert_eq!(turn_order(&mut app), vec![hen, bear, ram]);
+    }
+
+    #[test]
+    fn plan_matches_the_played_turn() {
+        let mut app = setup();
+        // the bear's best square depends on where the hen went before it
+        spawn_npc(&mut app, UnitKind::Hen, Vector2Int::new(0, 3), 0);
+        spawn_npc(&mut app, UnitKind::Bear, Vector2Int::new(0, 4), 1);
+        spawn_npc(&mut app, UnitKind::Frog, Vector2Int::new(2, 2), 2);
+        spawn_npc(&mut app, UnitKind::Stork, Vector2Int::new(3, 4), 3);
+
+        let planned = plan(&mut app);
+        assert_eq!(planned.len(), 4);
+        assert_eq!(planned, play_turn(&mut app));
+    }
+
+    #[test]
+    fn plan_skips_units_that_cant_act() {
+        let mut app = setup();
+        let hen = spawn_npc(&mut app, UnitKind::Hen, Vector2Int::new(0, 3), 0);
+        spawn_npc(&mut app, UnitKind::Bear, Vector2Int::new(0, 4), 1);
+        app.world.get_mut::<Unit>(hen).unwrap().effects.add(StatusEffect { kind: StatusKind::Stunned, turns: 1 });
+
+        let planned = plan(&mut app);
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned, play_turn(&mut app));
+    }
+}
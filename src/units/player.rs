@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::board::{
     Blocker, Board, Position,
@@ -6,8 +7,10 @@ use crate::board::{
     tile::TileInteractionEvent
 };
 use crate::items;
-use crate::items::Item;
-use crate::command::{CommandEvent, CommandType};
+use crate::items::{InventoryPrompt, Item, Relic};
+use crate::command::{CommandEvent, CommandResolvedEvent, CommandType, Outcome};
+use crate::events::GameEvent;
+use crate::settings::Settings;
 use crate::ui;
 use crate::states::{AnimationState, GameState};
 use crate::vectors::Vector2Int;
@@ -16,60 +19,187 @@ use super::behaviour::Behaviour;
 use super::data::get_unit_behaviour;
 use super::{Unit, UnitKind};
 
-const MAX_ITEMS: usize = 3;
+const BASE_ITEMS: usize = 3;
+pub const MAX_CARDS: usize = 3;
+const CARD_USES: u8 = 2;
 
 #[derive(Component)]
 pub struct Player;
 
+// how captured behaviours are handed over to the player, chosen in the settings
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatternMode {
+    // the captured pattern replaces the current one until the end of the turn
+    Steal,
+    // the captured pattern is stored as a move card with limited uses
+    #[default]
+    Hand
+}
+
+#[derive(Clone)]
+pub struct MoveCard {
+    pub kind: UnitKind,
+    pub behaviour: Behaviour,
+    pub uses: u8
+}
+
 pub struct PlayerData {
     pub current_behaviour: Behaviour,
+    // whose pattern the current behaviour is
+    pub pattern_kind: UnitKind,
     pub level: u32,
     pub items: Vec<Item>,
-    pub armor: u8
+    pub armor: u8,
+    pub hand: Vec<MoveCard>,
+    pub selected_card: Option<usize>,
+    // npc moves are shown for the rest of the level
+    pub reveal_intents: bool,
+    // walls do not block walking for the rest of the turn
+    pub phasing: bool,
+    pub relics: Vec<Relic>,
+    pub turn_captures: u32,
+    // earned by capturing, spent in the shop
+    pub coins: u32,
+    // any item used this run, spoils the no items achievement
+    pub used_item: bool
+}
+
+impl Default for PlayerData {
+    fn default() -> Self {
+        PlayerData {
+            current_behaviour: get_unit_behaviour(&UnitKind::Player),
+            pattern_kind: UnitKind::Player,
+            level: 0,
+            items: Vec::new(),
+            armor: 1,
+            hand: Vec::new(),
+            selected_card: None,
+            reveal_intents: false,
+            phasing: false,
+            relics: Vec::new(),
+            turn_captures: 0,
+            coins: 0,
+            used_item: false
+        }
+    }
+}
+
+impl PlayerData {
+    pub fn reset_behaviour(&mut self) {
+        self.current_behaviour = get_base_behaviour(&self.relics);
+        self.pattern_kind = UnitKind::Player;
+        self.selected_card = None;
+    }
+    pub fn add_relic(&mut self, relic: Relic) {
+        self.relics.push(relic);
+        if self.selected_card.is_none() { self.reset_behaviour(); }
+    }
+    pub fn relic_count(&self, relic: Relic) -> usize {
+        self.relics.iter().filter(|r| **r == relic).count()
+    }
+    pub fn max_items(&self) -> usize {
+        BASE_ITEMS + self.relic_count(Relic::ExtraSlot)
+    }
+    // a captured or copied pattern, as a card or straight away depending on the mode
+    pub fn learn_pattern(&mut self, kind: UnitKind, behaviour: Behaviour, mode: PatternMode) {
+        match mode {
+            PatternMode::Steal => {
+                self.current_behaviour = behaviour;
+                self.pattern_kind = kind;
+            },
+            PatternMode::Hand => {
+                if self.hand.len() >= MAX_CARDS {
+                    // the oldest card makes room for the fresh one
+                    self.hand.remove(0);
+                }
+                self.hand.push(MoveCard { kind, behaviour, uses: CARD_USES });
+            }
+        }
+    }
+    pub fn possible_positions(
+        &self,
+        source: Vector2Int,
+        board: &Board,
+        blockers: &Vec<(&Position, &Blocker)>
+    ) -> Vec<Vector2Int> {
+        if !self.phasing {
+            return self.current_behaviour.possible_positions(source, board, blockers);
+        }
+        // walls are skipped when checking the line, but still can't be entered
+        let targetable = blockers.iter()
+            .filter(|(_, b)| b.is_targetable)
+            .copied()
+            .collect();
+        self.current_behaviour.possible_positions(source, board, &targetable)
+            .into_iter()
+            .filter(|v| !blockers.iter().any(|(p, b)| p.v == *v && !b.is_targetable))
+            .collect()
+    }
 }
 
 pub fn reset_player_data(
     mut commands: Commands
 ) {
-    commands.insert_resource(PlayerData {
-        current_behaviour: super::data::get_unit_behaviour(&UnitKind::Player),
-        level: 0,
-        items: Vec::new(),
-        armor: 1
-    });
+    commands.insert_resource(PlayerData::default());
+}
+
+fn get_base_behaviour(relics: &[Relic]) -> Behaviour {
+    let mut behaviour = get_unit_behaviour(&UnitKind::Player);
+    if relics.contains(&Relic::ExtraSquare) {
+        behaviour.pattern.push(Vector2Int::new(0, -2));
+    }
+    behaviour
 }
 
 pub struct MovePlayerEvent(pub Vector2Int);
+pub struct SelectCardEvent(pub usize);
 
 pub fn start_player_turn(
     mut ev_ui: EventWriter<ui::RedrawUIEvent>,
+    mut ev_command: EventWriter<CommandEvent>,
     mut player_query: Query<(Entity, &mut Unit), With<Player>>,
     mut player_data: ResMut<PlayerData>,
 ) {
-    if let Ok((_entity, mut unit)) = player_query.get_single_mut() {
-        unit.handle_turn_start();
+    if let Ok((entity, mut unit)) = player_query.get_single_mut() {
+        if !unit.handle_turn_start() {
+            ev_command.send(CommandEvent(CommandType::KillUnit(entity)));
+            return;
+        }
 
-        player_data.current_behaviour = super::data::get_unit_behaviour(&UnitKind::Player);
-        if unit.ap > 0 {ev_ui.send(ui::RedrawUIEvent);}
+        player_data.reset_behaviour();
+        player_data.phasing = false;
+        player_data.turn_captures = 0;
+        if unit.ap > 0 {
+            let extra_ap = player_data.relic_count(Relic::ExtraAP) as u8;
+            if extra_ap > 0 {
+                ev_command.send(CommandEvent(CommandType::AddAP(entity, extra_ap)));
+            }
+            ev_ui.send(ui::RedrawUIEvent);
+        }
     }
 }
 
 pub fn player_status(
     mut game_state: ResMut<State<GameState>>,
+    mut ev_game: EventWriter<GameEvent>,
+    ev_command: Res<Events<CommandEvent>>,
+    player_data: Res<PlayerData>,
     player_query: Query<&Unit, With<Player>>
 ) {
     if let Ok(unit) = player_query.get_single() {
-        if unit.ap == 0 {
+        // queued commands (e.g. a capture refund) may still give ap back
+        if unit.ap == 0 && ev_command.is_empty() {
             game_state.set(GameState::NPCTurn);
         }
-    } else {
-        game_state.set(GameState::GameOver);
+    } else if game_state.set(GameState::GameOver).is_ok() {
+        ev_game.send(GameEvent::PlayerDied { level: player_data.level });
     }
 }
 
 pub fn move_player(
     mut ev_move: EventReader<MovePlayerEvent>,
-    mut query: Query<(Entity, &mut Position), With<Player>>,
+    mut ev_game: EventWriter<GameEvent>,
+    mut query: Query<(Entity, &mut Position, &Unit), With<Player>>,
     mut animation_state: ResMut<State<AnimationState>>,
     blocker_query: Query<(&Position, &Blocker), Without<Player>>,
     board_query: Query<&Board>,
@@ -77,33 +207,63 @@ pub fn move_player(
 ) {
     if animation_state.current() == &AnimationState::Animating { return ; }
     for ev in ev_move.iter() {
-        if let Ok((_, mut position)) = query.get_single_mut() {
+        if let Ok((entity, mut position, unit)) = query.get_single_mut() {
             let board = board_query.get_single().unwrap();
-            
-            let mut range = player_data.current_behaviour.possible_positions(
-                position.v, 
-                board,
-                &blocker_query.iter().collect()
+            let blockers = blocker_query.iter().collect();
+
+            let range = unit.effects.restrict_moves(
+                player_data.possible_positions(position.v, board, &blockers),
+                &blockers
             );
 
             if !range.contains(&ev.0) {
                 continue;
             }
 
-            position.v = ev.0;
+            let from = std::mem::replace(&mut position.v, ev.0);
+            ev_game.send(GameEvent::UnitMoved {
+                entity,
+                move_kind: player_data.current_behaviour.move_kind,
+                from,
+                to: ev.0
+            });
             animation_state.set(AnimationState::Animating);
         }
     }
 }
 
+pub fn select_card(
+    mut ev_select: EventReader<SelectCardEvent>,
+    mut ev_ui: EventWriter<ui::RedrawUIEvent>,
+    mut player_data: ResMut<PlayerData>,
+    animation_state: Res<State<AnimationState>>
+) {
+    for ev in ev_select.iter() {
+        if animation_state.current() == &AnimationState::Animating { continue; }
+        if player_data.selected_card == Some(ev.0) {
+            // clicking the selected card again goes back to the base pattern
+            player_data.reset_behaviour();
+        } else if let Some(card) = player_data.hand.get(ev.0) {
+            let kind = card.kind;
+            player_data.current_behaviour = card.behaviour.clone();
+            player_data.pattern_kind = kind;
+            player_data.selected_card = Some(ev.0);
+        }
+        ev_ui.send(ui::RedrawUIEvent);
+    }
+}
+
 pub fn tick(
     mut commands: Commands,
     game_state: Res<State<GameState>>,
+    settings: Res<Settings>,
     mut player_data: ResMut<PlayerData>,
     mut player_query: Query<(Entity, &mut Unit), With<Player>>,
     unit_position: Query<(Entity, &Position), With<Unit>>,
     unit_query: Query<&Unit, Without<Player>>,
     mut item_query: Query<(Entity, &Item, &Position)>,
+    mut prompt: ResMut<InventoryPrompt>,
+    mut ev_game: EventWriter<GameEvent>,
     mut ev_ui: EventWriter<ui::RedrawUIEvent>,
     mut ev_tile: EventWriter<TileInteractionEvent>,
     mut ev_command: EventWriter<CommandEvent>
@@ -113,11 +273,23 @@ pub fn tick(
     let (entity, mut unit) = player_query.get_single_mut().unwrap();
     let position = unit_position.get(entity).unwrap().1;
 
+    let pattern = player_data.pattern_kind;
+    if let PatternMode::Hand = settings.pattern_mode {
+        use_selected_card(&mut player_data);
+    }
+
     match super::check_unit_interaction(entity, position, &unit_position) {
         Some(attacked) => {
             let attacked_unit = unit_query.get(attacked).unwrap();                
-            player_data.current_behaviour = attacked_unit.behaviour.clone();
-            unit.ap += 1;
+            player_data.learn_pattern(attacked_unit.kind, attacked_unit.behaviour.clone(), settings.pattern_mode);
+            ev_game.send(GameEvent::PatternStolen { kind: attacked_unit.kind, pattern });
+            // a capture refunds the ap spent on the move
+            ev_command.send(CommandEvent(CommandType::AddAP(entity, 1)));
+            if player_data.turn_captures == 0 && player_data.relics.contains(&Relic::FreeCapture) {
+                ev_command.send(CommandEvent(CommandType::AddAP(entity, 1)));
+            }
+            player_data.turn_captures += 1;
+            player_data.coins += super::data::get_unit_rank(&attacked_unit.kind);
             ev_command.send(CommandEvent(CommandType::AttackUnit(entity, attacked)));
         },
         None => {}
@@ -127,7 +299,10 @@ pub fn tick(
         &mut commands,
         position,
         &mut item_query,
-        &mut player_data
+        &mut player_data,
+        &mut prompt,
+        &mut ev_command,
+        &mut ev_game
     );
 
     unit.handle_move_end();
@@ -136,6 +311,41 @@ pub fn tick(
     ev_tile.send(TileInteractionEvent(entity));
 }
 
+// teleports and swaps move units outside of their turn, the tile they land on still counts
+pub fn land_after_command(
+    mut commands: Commands,
+    mut ev_resolved: EventReader<CommandResolvedEvent>,
+    player_query: Query<(Entity, &Position), With<Player>>,
+    mut item_query: Query<(Entity, &Item, &Position)>,
+    mut player_data: ResMut<PlayerData>,
+    mut prompt: ResMut<InventoryPrompt>,
+    mut ev_tile: EventWriter<TileInteractionEvent>,
+    mut ev_command: EventWriter<CommandEvent>,
+    mut ev_game: EventWriter<GameEvent>
+) {
+    for ev in ev_resolved.iter() {
+        let moved = match (ev.0.command, ev.0.outcome) {
+            (CommandType::Teleport(e, _), Outcome::Moved { .. }) => vec![e],
+            (CommandType::SwapUnits(a, b), Outcome::Swapped { .. }) => vec![a, b],
+            _ => continue
+        };
+        for entity in moved {
+            ev_tile.send(TileInteractionEvent(entity));
+            if let Ok((_, position)) = player_query.get(entity) {
+                try_pick_item(
+                    &mut commands,
+                    position,
+                    &mut item_query,
+                    &mut player_data,
+                    &mut prompt,
+                    &mut ev_command,
+                    &mut ev_game
+                );
+            }
+        }
+    }
+}
+
 pub fn spawn_player(
     mut commands: &mut Commands,
     board: &Board,
@@ -152,29 +362,51 @@ pub fn spawn_player(
             ap: super::BASE_AP,
             behaviour: behaviour,
             kind: super::UnitKind::Player,
-            state: super::UnitState::Active
+            effects: super::status::StatusEffects::default()
         });
 
     Some(position)
 }
 
+fn use_selected_card(
+    player_data: &mut PlayerData
+) {
+    let idx = match player_data.selected_card {
+        Some(idx) => idx,
+        None => return
+    };
+    if let Some(card) = player_data.hand.get_mut(idx) {
+        card.uses = card.uses.saturating_sub(1);
+        if card.uses == 0 { player_data.hand.remove(idx); }
+    }
+    player_data.reset_behaviour();
+}
+
 fn try_pick_item (
-    mut commands: &mut Commands,
+    commands: &mut Commands,
     player_position: &Position,
-    mut item_query: &mut Query<(Entity, &Item, &Position)>,
-    mut player_data: &mut ResMut<PlayerData>,
+    item_query: &mut Query<(Entity, &Item, &Position)>,
+    player_data: &mut ResMut<PlayerData>,
+    prompt: &mut ResMut<InventoryPrompt>,
+    ev_command: &mut EventWriter<CommandEvent>,
+    ev_game: &mut EventWriter<GameEvent>
 ) {
+    prompt.item = None;
     for (entity, item, position) in item_query.iter() {
         if position.v != player_position.v { continue; }
 
-        if !items::data::is_passive(item.kind) && player_data.items.len() >= MAX_ITEMS { return; }
+        if !items::data::is_passive(item.kind) && player_data.items.len() >= player_data.max_items() {
+            // let the player decide what to do with the item
+            prompt.item = Some(entity);
+            return;
+        }
 
         commands.entity(entity).despawn_recursive();
+        ev_game.send(GameEvent::ItemPicked { kind: item.kind });
 
-        match items::data::is_passive(item.kind) {
-            false => player_data.items.push(item.clone()),
-            // for now only armor is possible -> change to command if other passive items are needed
-            true => player_data.armor += 1
-        };
+        match items::shop::get_passive_command(item.kind) {
+            Some(command) => ev_command.send(CommandEvent(command)),
+            None => player_data.items.push(item.clone())
+        }
     }
 }
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Blocker, Position};
+use crate::vectors::Vector2Int;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatusKind {
+    // no ap for the duration
+    Stunned,
+    // +1 ap at every turn start
+    Hasted,
+    // acts only every other turn
+    Slowed,
+    // can capture, but can't move to an empty tile
+    Rooted,
+    // the unit dies when the duration runs out
+    Poisoned
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    // turn starts left
+    pub turns: u8
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusKind, turns: u8) -> StatusEffect {
+        StatusEffect { kind, turns }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+// what the turn start did to the unit
+pub struct TurnStart {
+    pub ap: u8,
+    pub dead: bool
+}
+
+impl StatusEffects {
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.0.iter().any(|e| e.kind == kind)
+    }
+    pub fn add(&mut self, effect: StatusEffect) {
+        // haste and slow cancel each other out
+        let opposite = match effect.kind {
+            StatusKind::Hasted => Some(StatusKind::Slowed),
+            StatusKind::Slowed => Some(StatusKind::Hasted),
+            _ => None
+        };
+        if let Some(opposite) = opposite {
+            if self.has(opposite) {
+                self.0.retain(|e| e.kind != opposite);
+                return;
+            }
+        }
+        match self.0.iter_mut().find(|e| e.kind == effect.kind) {
+            Some(current) => match effect.kind {
+                // stuns pile up, poison gets faster
+                StatusKind::Stunned => current.turns = current.turns.saturating_add(effect.turns),
+                StatusKind::Poisoned => current.turns = current.turns.min(effect.turns),
+                // the rest is refreshed to the longer duration
+                _ => current.turns = current.turns.max(effect.turns)
+            },
+            None => self.0.push(effect)
+        }
+    }
+    // what the next turn start is going to do, without changing the effects
+    pub fn preview(&self, base_ap: u8) -> TurnStart {
+        self.clone().tick(base_ap)
+    }
+    pub fn tick(&mut self, base_ap: u8) -> TurnStart {
+        let mut ap = base_ap;
+        let mut dead = false;
+        for effect in self.0.iter_mut() {
+            match effect.kind {
+                StatusKind::Stunned => ap = 0,
+                StatusKind::Hasted => ap = ap.saturating_add(1),
+                StatusKind::Slowed => if effect.turns % 2 == 0 { ap = 0 },
+                StatusKind::Rooted => (),
+                StatusKind::Poisoned => if effect.turns <= 1 { dead = true }
+            }
+            effect.turns = effect.turns.saturating_sub(1);
+        }
+        // a stun overrides the haste
+        if self.has(StatusKind::Stunned) { ap = 0; }
+        self.0.retain(|e| e.turns > 0);
+        TurnStart { ap, dead }
+    }
+    // the strongest effect decides the tint
+    pub fn strongest(&self) -> Option<StatusKind> {
+        [
+            StatusKind::Stunned,
+            StatusKind::Poisoned,
+            StatusKind::Rooted,
+            StatusKind::Slowed,
+            StatusKind::Hasted
+        ].into_iter()
+            .find(|k| self.has(*k))
+    }
+    // removes moves that are not allowed by the current effects
+    pub fn restrict_moves(
+        &self,
+        positions: Vec<Vector2Int>,
+        blockers: &[(&Position, &Blocker)]
+    ) -> Vec<Vector2Int> {
+        if !self.has(StatusKind::Rooted) { return positions; }
+        positions.into_iter()
+            .filter(|v| blockers.iter().any(|(p, b)| p.v == *v && b.is_targetable))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::units::{Unit, UnitKind, data::get_unit_behaviour};
+    use super::*;
+
+    fn effects(list: &[(StatusKind, u8)]) -> StatusEffects {
+        let mut effects = StatusEffects::default();
+        for (kind, turns) in list {
+            effects.add(StatusEffect::new(*kind, *turns));
+        }
+        effects
+    }
+
+    fn turn_aps(effects: &mut StatusEffects, turns: usize) -> Vec<u8> {
+        (0..turns).map(|_| effects.tick(1).ap).collect()
+    }
+
+    fn unit(list: &[(StatusKind, u8)]) -> Unit {
+        Unit {
+            ap: 0,
+            behaviour: get_unit_behaviour(&UnitKind::Wolf),
+            kind: UnitKind::Wolf,
+            effects: effects(list)
+        }
+    }
+
+    #[test]
+    fn effects_count_down_and_expire() {
+        let mut stunned = effects(&[(StatusKind::Stunned, 2)]);
+        assert_eq!(turn_aps(&mut stunned, 3), [0, 0, 1]);
+        assert!(stunned.0.is_empty());
+
+        let mut hasted = effects(&[(StatusKind::Hasted, 2)]);
+        assert_eq!(turn_aps(&mut hasted, 3), [2, 2, 1]);
+
+        let mut slowed = effects(&[(StatusKind::Slowed, 4)]);
+        assert_eq!(turn_aps(&mut slowed, 5), [0, 1, 0, 1, 1]);
+
+        let mut poisoned = effects(&[(StatusKind::Poisoned, 3)]);
+        let dead: Vec<bool> = (0..3).map(|_| poisoned.tick(1).dead).collect();
+        assert_eq!(dead, [false, false, true]);
+    }
+
+    #[test]
+    fn stun_overrides_haste() {
+        let mut both = effects(&[(StatusKind::Hasted, 2), (StatusKind::Stunned, 1)]);
+        assert_eq!(turn_aps(&mut both, 2), [0, 2]);
+    }
+
+    #[test]
+    fn preview_does_not_tick() {
+        let effects = effects(&[(StatusKind::Poisoned, 1)]);
+        let start = effects.preview(1);
+        assert!(start.dead);
+        assert_eq!(effects.0[0].turns, 1);
+    }
+
+    #[test]
+    fn can_act_next_turn_follows_the_preview() {
+        assert!(unit(&[]).can_act_next_turn());
+        assert!(unit(&[(StatusKind::Hasted, 1)]).can_act_next_turn());
+        assert!(unit(&[(StatusKind::Rooted, 1)]).can_act_next_turn());
+        assert!(unit(&[(StatusKind::Slowed, 3)]).can_act_next_turn());
+        assert!(!unit(&[(StatusKind::Slowed, 2)]).can_act_next_turn());
+        assert!(!unit(&[(StatusKind::Stunned, 1)]).can_act_next_turn());
+        assert!(!unit(&[(StatusKind::Poisoned, 1)]).can_act_next_turn());
+        assert!(unit(&[(StatusKind::Poisoned, 2)]).can_act_next_turn());
+    }
+
+    #[test]
+    fn effects_stack_by_kind() {
+        // stuns pile up
+        let stunned = effects(&[(StatusKind::Stunned, 1), (StatusKind::Stunned, 2)]);
+        assert_eq!(stunned.0[0].turns, 3);
+        // poison keeps the shorter duration
+        let poisoned = effects(&[(StatusKind::Poisoned, 3), (StatusKind::Poisoned, 2)]);
+        assert_eq!(poisoned.0[0].turns, 2);
+        // the rest keep the longer one
+        let rooted = effects(&[(StatusKind::Rooted, 3), (StatusKind::Rooted, 2)]);
+        assert_eq!(rooted.0[0].turns, 3);
+        assert_eq!(rooted.0.len(), 1);
+        // haste and slow cancel each other
+        let cancelled = effects(&[(StatusKind::Hasted, 3), (StatusKind::Slowed, 2)]);
+        assert!(cancelled.0.is_empty());
+    }
+
+    #[test]
+    fn rooted_units_can_only_capture() {
+        let positions = vec![Vector2Int::new(0, 1), Vector2Int::new(1, 0), Vector2Int::new(1, 1)];
+        let npc = (Position { v: Vector2Int::new(1, 0) }, Blocker { is_targetable: true });
+        let wall = (Position { v: Vector2Int::new(1, 1) }, Blocker { is_targetable: false });
+        let blockers = vec![(&npc.0, &npc.1), (&wall.0, &wall.1)];
+
+        let free = effects(&[]);
+        assert_eq!(free.restrict_moves(positions.clone(), &blockers), positions);
+        let rooted = effects(&[(StatusKind::Rooted, 1)]);
+        assert_eq!(rooted.restrict_moves(positions, &blockers), [Vector2Int::new(1, 0)]);
+    }
+}